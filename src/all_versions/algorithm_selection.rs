@@ -0,0 +1,528 @@
+/// Automatic algorithm selection from instance features
+///
+/// `InstanceFeatures` are cheap to compute (one 1-nearest-neighbour pass):
+/// size, Clark–Evans clustering ratio, nearest-neighbour distance statistics,
+/// available threads and an optional time budget. The `Selector` predicts, for
+/// every benchmarked variant (`seq`, `topkplus` with each measured `k`, `mult3`,
+/// `mult4`, `ga3`), the tour quality and the run time on the instance, and
+/// picks the best predicted quality that fits the budget.
+///
+/// Predictions come from the benchmark CSVs in `raw_dev/results`, embedded at
+/// compile time:
+/// - quality is the tour length relative to sequential 2-opt on the same
+///   instance size: the median over the benchmarked sizes with weights
+///   decaying in log-size distance, and the weighted median deviation from it
+///   as its spread. Lengths are normalised by `sqrt(n * A)` (Beardwood–Halton–Hammersley) so the
+///   two-opt CSVs (1000 x 1000 square) and GA CSVs (100 x 100) compare
+/// - time is scaled from the nearest size with a per-variant power law fitted
+///   on the measured sizes, and from the measured core count to `threads` along
+///   the thread-scaling curves of `scalability.csv` / `parallelism.csv`
+///
+/// The selection minimises quality plus spread. Every benchmark instance is
+/// uniform random, so the clustering ratio and the nearest-neighbour spread do
+/// not enter the predictions; they only decide whether the instance is in the
+/// benchmarked range, and outside it the spread counts twice.
+/// `validate` replays the selection with each benchmark size left out.
+use std::io;
+use std::time::Duration;
+use crate::all_versions::local_search::neighbor_lists;
+use crate::all_versions::optimized_ver2_multi2opt::*;
+use crate::all_versions::par_ga::*;
+use crate::all_versions::par_topkplus::*;
+use crate::all_versions::random_insert_ver3_multi2opt::*;
+use crate::all_versions::two_opt_seq::*;
+use crate::all_versions::utils::*;
+
+/// Cores of the machine the two-opt and GA CSVs were measured on: their n=1000
+/// times match the 4-8 core rows of `scalability.csv`
+const BENCH_CORES: usize = 8;
+/// Power law used for the size scaling of variants measured at a single size
+const DEFAULT_TIME_EXPONENT: f64 = 2.0;
+/// Coefficient of variation of nearest-neighbour distances in a uniform
+/// (Poisson) point set: sqrt(4 / pi - 1)
+const UNIFORM_NN_CV: f64 = 0.5227;
+/// Width, in log size, of the kernel weighting measured quality ratios over
+/// sizes. A single size is too noisy: the ratios of one variant move by a few
+/// percent between sizes without any trend in n
+const RATIO_BANDWIDTH: f64 = 0.3;
+/// Top-k++ runs ending more than this far above seq (31-170% above, 5 of the
+/// 20 rows) stopped far from any 2-opt local optimum: they applied crossing
+/// moves together, which `batch_moves` now rules out. They are dropped.
+const DIVERGED_RATIO: f64 = 1.25;
+/// Weight of the measured spread for instances outside the benchmarked range
+const EXTRAPOLATION_MARGIN: f64 = 2.0;
+
+const TWO_OPT_CSVS: [(usize, &str); 5] = [
+    (50, include_str!("../../raw_dev/results/two_opt/results_50.csv")),
+    (100, include_str!("../../raw_dev/results/two_opt/results_100.csv")),
+    (200, include_str!("../../raw_dev/results/two_opt/results_200.csv")),
+    (500, include_str!("../../raw_dev/results/two_opt/results_500.csv")),
+    (1000, include_str!("../../raw_dev/results/two_opt/results_1000.csv")),
+];
+const GA_CSV: &str = include_str!("../../raw_dev/results/GA/results.csv");
+const PARALLELISM_CSV: &str = include_str!("../../raw_dev/results/parallelism.csv");
+const SCALABILITY_CSV: &str = include_str!("../../raw_dev/results/scalability.csv");
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Variant {
+    Seq,
+    TopKPlus { k: usize, delta_thresh: f64 },
+    Mult3,
+    Mult4,
+    Ga3,
+}
+
+impl Variant {
+    /// Runner version name (`main_scalability` / `main_parallelism`)
+    pub fn version(&self) -> &'static str {
+        match self {
+            Variant::Seq => "seq",
+            Variant::TopKPlus { .. } => "topkplus",
+            Variant::Mult3 => "mult3",
+            Variant::Mult4 => "mult4",
+            Variant::Ga3 => "ga3",
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Variant::TopKPlus { k, delta_thresh } => format!("topkplus(k={}, delta={:e})", k, delta_thresh),
+            v => v.version().to_string(),
+        }
+    }
+
+    /// Run the variant with the runners' parameters
+    pub fn run(&self, tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+        match *self {
+            Variant::Seq => two_opt_seq(tour, cities),
+            Variant::TopKPlus { k, delta_thresh } => par_topkplus(tour, cities, k, delta_thresh),
+            Variant::Mult3 => multi_2opt_optimized2_V2(tour, cities),
+            Variant::Mult4 => multi_2opt_random_insert(tour, cities),
+            Variant::Ga3 => {
+                let best = run_ga_parallel(cities, 100, 300, 0.1, 5, 100, 100, 10);
+                let cost = best.distance();
+                (best.tour, cost)
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct InstanceFeatures {
+    pub n: usize,
+    /// Bounding-box area
+    pub area: f64,
+    /// Mean nearest-neighbour distance over its expectation in a uniform point
+    /// set of the same density: < 1 clustered, ~1 random, > 1 regular
+    pub clark_evans: f64,
+    pub nn_mean: f64,
+    /// Coefficient of variation of the nearest-neighbour distances
+    pub nn_cv: f64,
+    pub threads: usize,
+    pub budget: Option<Duration>,
+}
+
+impl InstanceFeatures {
+    pub fn compute(cities: &[City], threads: usize, budget: Option<Duration>) -> Self {
+        let n = cities.len();
+        let (mut min_x, mut max_x, mut min_y, mut max_y) = (f64::MAX, f64::MIN, f64::MAX, f64::MIN);
+        for c in cities {
+            min_x = min_x.min(c.x);
+            max_x = max_x.max(c.x);
+            min_y = min_y.min(c.y);
+            max_y = max_y.max(c.y);
+        }
+        let area = if n > 0 { ((max_x - min_x) * (max_y - min_y)).max(f64::MIN_POSITIVE) } else { 0.0 };
+
+        let (mut nn_mean, mut nn_cv, mut clark_evans) = (0.0, 0.0, 1.0);
+        if n >= 2 {
+            let nearest: Vec<f64> = neighbor_lists(cities, 1)
+                .iter()
+                .enumerate()
+                .map(|(i, nb)| euclidean_distance(&cities[i], &cities[nb[0]]))
+                .collect();
+            nn_mean = nearest.iter().sum::<f64>() / n as f64;
+            let var = nearest.iter().map(|d| (d - nn_mean).powi(2)).sum::<f64>() / n as f64;
+            nn_cv = if nn_mean > 0.0 { var.sqrt() / nn_mean } else { 0.0 };
+            clark_evans = nn_mean / (0.5 * (area / n as f64).sqrt());
+        }
+        Self { n, area, clark_evans, nn_mean, nn_cv, threads: threads.max(1), budget }
+    }
+
+    /// Whether the instance resembles the benchmark instances (uniform random,
+    /// 50..=1000 cities); predictions outside this range are extrapolated
+    pub fn in_benchmark_range(&self) -> bool {
+        (50..=1000).contains(&self.n)
+            && (0.8..=1.2).contains(&self.clark_evans)
+            && (self.nn_cv - UNIFORM_NN_CV).abs() <= 0.15
+    }
+}
+
+/// One benchmark measurement
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Benchmark {
+    pub n: usize,
+    pub cores: usize,
+    pub variant: Variant,
+    /// Tour length / sequential 2-opt tour length at the same size
+    pub ratio: f64,
+    pub time_ms: f64,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct Prediction {
+    pub variant: Variant,
+    pub ratio: f64,
+    /// Weighted median deviation of the measured ratios from `ratio`
+    pub spread: f64,
+    pub time_ms: f64,
+}
+
+/// Rows of a CSV (header skipped), split on commas
+fn rows(csv: &str) -> impl Iterator<Item = Vec<&str>> {
+    csv.lines().skip(1).filter(|l| !l.trim().is_empty()).map(|l| l.split(',').map(str::trim).collect())
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Text of column `i` ("" if the row is shorter)
+fn text<'a>(row: &[&'a str], i: usize) -> &'a str {
+    row.get(i).copied().unwrap_or("")
+}
+
+/// Number in column `i`
+fn num(row: &[&str], i: usize) -> io::Result<f64> {
+    let field = text(row, i);
+    field
+        .parse()
+        .map_err(|_| invalid(format!("bad number {:?} in column {} of benchmark row {:?}", field, i, row.join(","))))
+}
+
+/// Median of `(value, weight)` pairs: the smallest value holding at least half
+/// of the total weight
+fn weighted_median(values: &[(f64, f64)]) -> f64 {
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let half = sorted.iter().map(|v| v.1).sum::<f64>() / 2.0;
+    let mut acc = 0.0;
+    for &(value, weight) in &sorted {
+        acc += weight;
+        if acc >= half {
+            return value;
+        }
+    }
+    sorted.last().map_or(1.0, |v| v.0)
+}
+
+/// BHH-normalised tour length on a `side` x `side` square
+fn normalized(cost: f64, n: usize, side: f64) -> f64 {
+    cost / (n as f64 * side * side).sqrt()
+}
+
+/// All embedded benchmark measurements of the selectable variants
+#[allow(dead_code)]
+pub fn benchmarks() -> io::Result<Vec<Benchmark>> {
+    let mut out = Vec::new();
+    let mut seq_quality = Vec::new();
+
+    for (n, csv) in TWO_OPT_CSVS {
+        let parsed: Vec<Vec<&str>> = rows(csv).collect();
+        let seq_row = parsed
+            .iter()
+            .find(|r| text(r, 0) == "sequential")
+            .ok_or_else(|| invalid(format!("no sequential row in the n={} two-opt CSV", n)))?;
+        let seq = num(seq_row, 3)?;
+        seq_quality.push((n, normalized(seq, n, 1000.0)));
+        for r in &parsed {
+            let variant = match text(r, 0) {
+                "sequential" => Variant::Seq,
+                // delta_thresh never changes the final cost; keep the runners' 1e-5
+                "topk++" if text(r, 2) == "1e-5" => Variant::TopKPlus { k: num(r, 1)? as usize, delta_thresh: num(r, 2)? },
+                _ => continue,
+            };
+            let ratio = num(r, 3)? / seq;
+            if ratio > DIVERGED_RATIO {
+                continue;
+            }
+            out.push(Benchmark { n, cores: BENCH_CORES, variant, ratio, time_ms: num(r, 4)? });
+        }
+    }
+
+    // Parallel GA runs have no sequential reference on the same instances
+    for r in rows(GA_CSV).filter(|r| text(r, 2) == "Parallel GA") {
+        let n = num(&r, 0)? as usize;
+        if let Some(&(_, seq)) = seq_quality.iter().find(|&&(m, _)| m == n) {
+            let ratio = normalized(num(&r, 3)?, n, 100.0) / seq;
+            out.push(Benchmark { n, cores: BENCH_CORES, variant: Variant::Ga3, ratio, time_ms: num(&r, 4)? * 1000.0 });
+        }
+    }
+
+    let parsed: Vec<Vec<&str>> = rows(PARALLELISM_CSV).collect();
+    for r in &parsed {
+        let variant = match text(r, 2) {
+            "seq" => Variant::Seq,
+            "topkplus" => Variant::TopKPlus { k: 10, delta_thresh: 1e-5 },
+            "mult3" => Variant::Mult3,
+            "mult4" => Variant::Mult4,
+            "ga3" => Variant::Ga3,
+            _ => continue,
+        };
+        let seq_row = parsed
+            .iter()
+            .find(|s| text(s, 2) == "seq" && text(s, 0) == text(r, 0) && text(s, 1) == text(r, 1))
+            .ok_or_else(|| invalid(format!("no seq row for the same cores and size as {:?}", r.join(","))))?;
+        out.push(Benchmark {
+            n: num(r, 1)? as usize,
+            cores: num(r, 0)? as usize,
+            variant,
+            ratio: num(r, 3)? / num(seq_row, 3)?,
+            time_ms: num(r, 4)?,
+        });
+    }
+    Ok(out)
+}
+
+/// Runner version and its (cores, time) points
+type ThreadCurve = (&'static str, Vec<(f64, f64)>);
+
+/// Time vs cores per runner version, from `scalability.csv` (topkplus, ga3)
+/// and `parallelism.csv` (1 and 32 cores, the other versions)
+fn thread_curves() -> io::Result<Vec<ThreadCurve>> {
+    let scal: Vec<Vec<&str>> = rows(SCALABILITY_CSV).collect();
+    let mut curves = vec![
+        ("topkplus", scal.iter().map(|r| Ok((num(r, 0)?, num(r, 2)?))).collect::<io::Result<Vec<_>>>()?),
+        ("ga3", scal.iter().map(|r| Ok((num(r, 0)?, num(r, 4)?))).collect::<io::Result<Vec<_>>>()?),
+    ];
+    for version in ["seq", "mult3", "mult4"] {
+        let points = rows(PARALLELISM_CSV)
+            .filter(|r| text(r, 2) == version)
+            .map(|r| Ok((num(&r, 0)?, num(&r, 4)?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        curves.push((version, points));
+    }
+    Ok(curves)
+}
+
+/// Piecewise log-log interpolation of `curve` at `x`, clamped to its range
+fn interpolate(curve: &[(f64, f64)], x: f64) -> f64 {
+    let (first, last) = (curve[0], curve[curve.len() - 1]);
+    if x <= first.0 {
+        return first.1;
+    }
+    if x >= last.0 {
+        return last.1;
+    }
+    let i = curve.windows(2).position(|w| x <= w[1].0).unwrap();
+    let ((x0, y0), (x1, y1)) = (curve[i], curve[i + 1]);
+    let s = (x.ln() - x0.ln()) / (x1.ln() - x0.ln());
+    (y0.ln() + s * (y1.ln() - y0.ln())).exp()
+}
+
+pub struct Selector {
+    benchmarks: Vec<Benchmark>,
+    curves: Vec<ThreadCurve>,
+}
+
+impl Selector {
+    /// Selector over all embedded benchmark CSVs
+    #[allow(dead_code)]
+    pub fn from_csv() -> io::Result<Self> {
+        Self::from_benchmarks(benchmarks()?)
+    }
+
+    pub fn from_benchmarks(benchmarks: Vec<Benchmark>) -> io::Result<Self> {
+        Ok(Self { benchmarks, curves: thread_curves()? })
+    }
+
+    fn variants(&self) -> Vec<Variant> {
+        let mut out: Vec<Variant> = Vec::new();
+        for b in &self.benchmarks {
+            if !out.contains(&b.variant) {
+                out.push(b.variant);
+            }
+        }
+        out
+    }
+
+    /// Time at `to` cores relative to `from` cores
+    fn thread_factor(&self, variant: Variant, from: usize, to: usize) -> f64 {
+        match self.curves.iter().find(|(v, _)| *v == variant.version()) {
+            Some((_, curve)) => interpolate(curve, to as f64) / interpolate(curve, from as f64),
+            None => 1.0,
+        }
+    }
+
+    /// Least-squares slope of log time against log n over the sizes measured
+    /// at `BENCH_CORES`
+    fn time_exponent(records: &[&Benchmark]) -> f64 {
+        let points: Vec<(f64, f64)> = records
+            .iter()
+            .filter(|b| b.cores == BENCH_CORES)
+            .map(|b| ((b.n as f64).ln(), b.time_ms.ln()))
+            .collect();
+        let mean_x = points.iter().map(|p| p.0).sum::<f64>() / points.len().max(1) as f64;
+        let mean_y = points.iter().map(|p| p.1).sum::<f64>() / points.len().max(1) as f64;
+        let sxx: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+        if sxx < 1e-9 {
+            return DEFAULT_TIME_EXPONENT;
+        }
+        points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum::<f64>() / sxx
+    }
+
+    /// Predicted quality and time of every benchmarked variant
+    pub fn predict(&self, features: &InstanceFeatures) -> Vec<Prediction> {
+        let n = features.n.max(2) as f64;
+        self.variants()
+            .into_iter()
+            .map(|variant| {
+                let records: Vec<&Benchmark> = self.benchmarks.iter().filter(|b| b.variant == variant).collect();
+                let nearest = records
+                    .iter()
+                    .map(|b| b.n)
+                    .min_by(|&a, &b| (a as f64 / n).ln().abs().partial_cmp(&(b as f64 / n).ln().abs()).unwrap())
+                    .unwrap();
+                let at_nearest: Vec<&&Benchmark> = records.iter().filter(|b| b.n == nearest).collect();
+                let weighted: Vec<(f64, f64)> = records
+                    .iter()
+                    .map(|b| (b.ratio, (-(b.n as f64 / n).ln().abs() / RATIO_BANDWIDTH).exp()))
+                    .collect();
+                let ratio = weighted_median(&weighted);
+                let deviations: Vec<(f64, f64)> = weighted.iter().map(|&(r, w)| ((r - ratio).abs(), w)).collect();
+                let spread = weighted_median(&deviations);
+                let size_factor = (n / nearest as f64).powf(Self::time_exponent(&records));
+                let time_ms = at_nearest
+                    .iter()
+                    .map(|b| b.time_ms * self.thread_factor(variant, b.cores, features.threads))
+                    .sum::<f64>()
+                    / at_nearest.len() as f64
+                    * size_factor;
+                Prediction { variant, ratio, spread, time_ms }
+            })
+            .collect()
+    }
+
+    /// Best pessimistic quality (`ratio` plus `spread`, the spread counted
+    /// `EXTRAPOLATION_MARGIN` times outside the benchmarked range) within the
+    /// budget, or the fastest variant if none fits, among the variants accepted
+    /// by `allowed`. Seq has no spread, so another variant is only chosen when
+    /// its predicted gain exceeds its measured spread.
+    pub fn select_from(&self, features: &InstanceFeatures, allowed: impl Fn(&Variant) -> bool) -> Prediction {
+        let candidates: Vec<Prediction> = self.predict(features).into_iter().filter(|p| allowed(&p.variant)).collect();
+        let budget_ms = features.budget.map_or(f64::INFINITY, |b| b.as_secs_f64() * 1000.0);
+        let margin = if features.in_benchmark_range() { 1.0 } else { EXTRAPOLATION_MARGIN };
+        let by = |a: &&Prediction, b: &&Prediction| {
+            (a.ratio + margin * a.spread, a.time_ms).partial_cmp(&(b.ratio + margin * b.spread, b.time_ms)).unwrap()
+        };
+        candidates
+            .iter()
+            .filter(|p| p.time_ms <= budget_ms)
+            .min_by(by)
+            .or_else(|| candidates.iter().min_by(|a, b| a.time_ms.partial_cmp(&b.time_ms).unwrap()))
+            .cloned()
+            .expect("selector has no benchmarks")
+    }
+
+    pub fn select(&self, features: &InstanceFeatures) -> Prediction {
+        self.select_from(features, |_| true)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct ValidationReport {
+    /// (held-out size, budget) cases replayed
+    pub cases: usize,
+    /// Cases where the selected variant was the measured best within budget
+    pub agreements: usize,
+    /// Mean / max measured length of the selection over the measured best, in %
+    pub mean_regret: f64,
+    pub max_regret: f64,
+    /// Cases where the selection's measured time exceeded the budget
+    pub over_budget: usize,
+    /// Mean regret of always running `seq`, for reference
+    pub seq_regret: f64,
+}
+
+/// Leave-one-size-out validation on the two-opt / GA benchmark sizes: for every
+/// size, a selector built from the other sizes chooses among the variants
+/// measured at that size, without a budget and with each measured time as the
+/// budget; the choice is scored against the measurements
+#[allow(dead_code)]
+pub fn validate() -> io::Result<ValidationReport> {
+    let all = benchmarks()?;
+    let mut report = ValidationReport { cases: 0, agreements: 0, mean_regret: 0.0, max_regret: 0.0, over_budget: 0, seq_regret: 0.0 };
+
+    for (n, _) in TWO_OPT_CSVS {
+        let selector = Selector::from_benchmarks(all.iter().filter(|b| b.n != n).cloned().collect())?;
+        let held_out: Vec<&Benchmark> = all.iter().filter(|b| b.n == n && b.cores == BENCH_CORES).collect();
+        // measured (ratio, time) per variant, averaged over repeated runs
+        let mut measured: Vec<(Variant, f64, f64)> = Vec::new();
+        for b in &held_out {
+            if measured.iter().all(|m| m.0 != b.variant) {
+                let runs: Vec<&&Benchmark> = held_out.iter().filter(|o| o.variant == b.variant).collect();
+                let ratio = runs.iter().map(|o| o.ratio).sum::<f64>() / runs.len() as f64;
+                let time = runs.iter().map(|o| o.time_ms).sum::<f64>() / runs.len() as f64;
+                measured.push((b.variant, ratio, time));
+            }
+        }
+
+        let budgets = std::iter::once(None).chain(measured.iter().map(|m| Some(m.2)));
+        for budget_ms in budgets {
+            let features = InstanceFeatures {
+                n,
+                area: 1.0,
+                clark_evans: 1.0,
+                nn_mean: 0.0,
+                nn_cv: UNIFORM_NN_CV,
+                threads: BENCH_CORES,
+                budget: budget_ms.map(|ms| Duration::from_secs_f64(ms / 1000.0)),
+            };
+            let chosen = selector.select_from(&features, |v| measured.iter().any(|m| m.0 == *v)).variant;
+            let limit = budget_ms.unwrap_or(f64::INFINITY);
+            let best = measured
+                .iter()
+                .filter(|m| m.2 <= limit)
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap();
+            let got = measured.iter().find(|m| m.0 == chosen).unwrap();
+
+            let regret = (got.1 / best.1 - 1.0) * 100.0;
+            report.cases += 1;
+            report.agreements += (got.1 <= best.1 + 1e-12) as usize;
+            report.mean_regret += regret.max(0.0);
+            report.max_regret = report.max_regret.max(regret);
+            report.over_budget += (got.2 > limit) as usize;
+            let seq = measured.iter().find(|m| m.0 == Variant::Seq).unwrap();
+            report.seq_regret += (seq.1 / best.1 - 1.0) * 100.0;
+        }
+    }
+    report.mean_regret /= report.cases.max(1) as f64;
+    report.seq_regret /= report.cases.max(1) as f64;
+    Ok(report)
+}
+
+/// Compute the features of `cities`, select a variant and run it. Fails only if
+/// the embedded benchmark CSVs cannot be parsed.
+#[allow(dead_code)]
+pub fn solve_auto(tour: &[usize], cities: &[City], budget: Option<Duration>) -> io::Result<(Vec<usize>, f64)> {
+    let features = InstanceFeatures::compute(cities, rayon::current_num_threads(), budget);
+    let choice = Selector::from_csv()?.select(&features);
+    println!(
+        "Features: n = {}, Clark-Evans = {:.2}, NN mean = {:.2} (cv {:.2}), threads = {}, budget = {:?}",
+        features.n, features.clark_evans, features.nn_mean, features.nn_cv, features.threads, features.budget
+    );
+    println!(
+        "Selected {}: predicted {:.3}x seq length in {:.1}ms{}",
+        choice.variant.label(),
+        choice.ratio,
+        choice.time_ms,
+        if features.in_benchmark_range() { "" } else { " (outside the benchmarked range)" }
+    );
+    Ok(choice.variant.run(tour, cities))
+}
@@ -0,0 +1,279 @@
+/// Parallel ant colony optimisation: Ant System and MAX-MIN Ant System
+///
+/// Each iteration, `ants` tours are built in parallel (one RNG stream per ant).
+/// An ant at city `i` chooses among the unvisited cities of `i`'s candidate list
+/// with probability proportional to `tau^alpha * eta^beta` (eta = 1 / distance),
+/// and falls back to the nearest unvisited city when the whole list is visited.
+/// Pheromone deposits are reduced deterministically: every ant produces its own
+/// list of edge deposits, which are summed in ant order after the parallel phase.
+/// The iteration-best tour can be polished with `utils::two_opt` before updating.
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::construction::nearest_neighbor_tour;
+use crate::all_versions::local_search::neighbor_lists;
+use crate::all_versions::utils::*;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AcoVariant {
+    /// Every ant deposits `1 / L` on its edges
+    AntSystem,
+    /// Only the iteration-best (or, every `global_every` iterations, the global
+    /// best) deposits; pheromone is clamped to `[tau_min, tau_max]`
+    MaxMin,
+}
+
+#[derive(Clone, Debug)]
+pub struct AcoConfig {
+    pub variant: AcoVariant,
+    /// Ants per iteration (0 = number of cities, capped at 100)
+    pub ants: usize,
+    pub alpha: f64,
+    pub beta: f64,
+    /// Evaporation rate
+    pub rho: f64,
+    pub candidates_k: usize,
+    /// Polish the iteration-best tour with `two_opt` before the pheromone update
+    pub two_opt_best: bool,
+    /// MMAS: deposit with the global best every `global_every` iterations
+    pub global_every: usize,
+    /// One iteration = one colony generation
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for AcoConfig {
+    fn default() -> Self {
+        Self {
+            variant: AcoVariant::MaxMin,
+            ants: 0,
+            alpha: 1.0,
+            beta: 3.0,
+            rho: 0.1,
+            candidates_k: 15,
+            two_opt_best: true,
+            global_every: 10,
+            stop: StopCondition { max_iterations: 200, time_limit: None, patience: 50 },
+            seed: 121,
+        }
+    }
+}
+
+/// Pheromone convergence statistics for one iteration
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct PheromoneStats {
+    pub iteration: usize,
+    pub best: f64,
+    pub iteration_best: f64,
+    pub tau_min: f64,
+    pub tau_max: f64,
+    pub tau_mean: f64,
+    /// Average lambda-branching factor (lambda = 0.05): mean number of candidate
+    /// edges per city with tau >= min + 0.05 * (max - min). Approaches 1 as the
+    /// colony converges.
+    pub branching: f64,
+}
+
+/// Dense symmetric pheromone matrix
+struct Pheromone {
+    n: usize,
+    tau: Vec<f64>,
+}
+
+impl Pheromone {
+    fn get(&self, a: usize, b: usize) -> f64 {
+        self.tau[a * self.n + b]
+    }
+
+    fn add(&mut self, a: usize, b: usize, amount: f64) {
+        self.tau[a * self.n + b] += amount;
+        self.tau[b * self.n + a] += amount;
+    }
+}
+
+fn build_tour(
+    cities: &[City],
+    candidates: &[Vec<usize>],
+    pheromone: &Pheromone,
+    config: &AcoConfig,
+    rng: &mut StdRng,
+) -> Vec<usize> {
+    let n = cities.len();
+    let mut visited = vec![false; n];
+    let mut tour = Vec::with_capacity(n);
+    let mut current = rng.gen_range(0..n);
+    tour.push(current);
+    visited[current] = true;
+
+    let mut weights = Vec::with_capacity(config.candidates_k);
+    for _ in 1..n {
+        weights.clear();
+        let mut total = 0.0;
+        for &c in &candidates[current] {
+            if !visited[c] {
+                let eta = 1.0 / euclidean_distance(&cities[current], &cities[c]).max(1e-9);
+                let w = pheromone.get(current, c).powf(config.alpha) * eta.powf(config.beta);
+                total += w;
+                weights.push((c, w));
+            }
+        }
+
+        let next = if weights.is_empty() {
+            // candidate list exhausted: nearest unvisited city
+            (0..n)
+                .filter(|&c| !visited[c])
+                .min_by(|&a, &b| {
+                    euclidean_distance(&cities[current], &cities[a])
+                        .partial_cmp(&euclidean_distance(&cities[current], &cities[b]))
+                        .unwrap()
+                })
+                .unwrap()
+        } else {
+            let mut r = rng.gen::<f64>() * total;
+            let mut chosen = weights[weights.len() - 1].0;
+            for &(c, w) in &weights {
+                if r < w {
+                    chosen = c;
+                    break;
+                }
+                r -= w;
+            }
+            chosen
+        };
+
+        tour.push(next);
+        visited[next] = true;
+        current = next;
+    }
+    tour
+}
+
+fn tour_edges(tour: &[usize]) -> impl Iterator<Item = (usize, usize)> + '_ {
+    (0..tour.len()).map(move |i| (tour[i], tour[(i + 1) % tour.len()]))
+}
+
+fn stats(iteration: usize, best: f64, iteration_best: f64, pheromone: &Pheromone, candidates: &[Vec<usize>]) -> PheromoneStats {
+    let (mut lo, mut hi, mut sum, mut count) = (f64::INFINITY, f64::NEG_INFINITY, 0.0, 0usize);
+    for (a, list) in candidates.iter().enumerate() {
+        for &b in list {
+            let t = pheromone.get(a, b);
+            lo = lo.min(t);
+            hi = hi.max(t);
+            sum += t;
+            count += 1;
+        }
+    }
+    let cutoff = lo + 0.05 * (hi - lo);
+    let above = candidates
+        .iter()
+        .enumerate()
+        .map(|(a, list)| list.iter().filter(|&&b| pheromone.get(a, b) >= cutoff).count())
+        .sum::<usize>();
+    PheromoneStats {
+        iteration,
+        best,
+        iteration_best,
+        tau_min: lo,
+        tau_max: hi,
+        tau_mean: sum / count.max(1) as f64,
+        branching: above as f64 / candidates.len() as f64,
+    }
+}
+
+/// Run the colony from scratch (ACO is constructive, so no start tour).
+/// Returns the best tour, its length, and per-iteration pheromone statistics.
+#[allow(dead_code)]
+pub fn ant_colony(cities: &[City], config: &AcoConfig) -> (Vec<usize>, f64, Vec<PheromoneStats>) {
+    let n = cities.len();
+    let start = Instant::now();
+    let candidates = neighbor_lists(cities, config.candidates_k);
+    let ants = if config.ants == 0 { n.min(100) } else { config.ants };
+
+    // Initial pheromone from a nearest-neighbour tour length
+    let nn_cost = compute_total_distance(&nearest_neighbor_tour(0, cities), cities);
+    let (tau0, mut tau_max, mut tau_min) = match config.variant {
+        AcoVariant::AntSystem => (ants as f64 / nn_cost, f64::INFINITY, 0.0),
+        AcoVariant::MaxMin => {
+            let max = 1.0 / (config.rho * nn_cost);
+            (max, max, max / (2.0 * n as f64))
+        }
+    };
+    let mut pheromone = Pheromone { n, tau: vec![tau0; n * n] };
+
+    let mut best: Vec<usize> = (0..n).collect();
+    let mut best_cost = f64::INFINITY;
+    let mut history = Vec::new();
+    let mut since_improvement = 0;
+
+    let mut iteration = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) {
+        // Parallel tour construction, one RNG stream per ant
+        let tours: Vec<(Vec<usize>, f64)> = (0..ants)
+            .into_par_iter()
+            .map(|ant| {
+                let mut rng = stream_rng(config.seed, iteration as u64, ant as u64);
+                let tour = build_tour(cities, &candidates, &pheromone, config, &mut rng);
+                let cost = compute_total_distance(&tour, cities);
+                (tour, cost)
+            })
+            .collect();
+
+        let (mut it_best, mut it_cost) = tours
+            .iter()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .cloned()
+            .unwrap();
+        if config.two_opt_best {
+            it_best = two_opt(&it_best, cities);
+            it_cost = compute_total_distance(&it_best, cities);
+        }
+        if it_cost < best_cost - 1e-9 {
+            best_cost = it_cost;
+            best = it_best.clone();
+            since_improvement = 0;
+            if config.variant == AcoVariant::MaxMin {
+                tau_max = 1.0 / (config.rho * best_cost);
+                tau_min = tau_max / (2.0 * n as f64);
+            }
+        } else {
+            since_improvement += 1;
+        }
+
+        // Evaporation (parallel over rows), then deterministic deposit
+        pheromone.tau.par_iter_mut().for_each(|t| *t *= 1.0 - config.rho);
+        match config.variant {
+            AcoVariant::AntSystem => {
+                let deposits: Vec<Vec<(usize, usize, f64)>> = tours
+                    .par_iter()
+                    .map(|(tour, cost)| tour_edges(tour).map(|(a, b)| (a, b, 1.0 / cost)).collect())
+                    .collect();
+                for (a, b, amount) in deposits.into_iter().flatten() {
+                    pheromone.add(a, b, amount);
+                }
+            }
+            AcoVariant::MaxMin => {
+                let use_global = config.global_every > 0 && iteration % config.global_every == 0;
+                let (tour, cost) = if use_global { (&best, best_cost) } else { (&it_best, it_cost) };
+                for (a, b) in tour_edges(tour) {
+                    pheromone.add(a, b, 1.0 / cost);
+                }
+                pheromone.tau.par_iter_mut().for_each(|t| *t = t.clamp(tau_min, tau_max));
+            }
+        }
+
+        let s = stats(iteration, best_cost, it_cost, &pheromone, &candidates);
+        if iteration % 20 == 0 {
+            println!(
+                "Iteration {}: best = {:.2} | iteration best = {:.2} | tau [{:.2e}, {:.2e}] | branching = {:.2}",
+                iteration, s.best, s.iteration_best, s.tau_min, s.tau_max, s.branching
+            );
+        }
+        history.push(s);
+        iteration += 1;
+    }
+
+    (best, best_cost, history)
+}
@@ -0,0 +1,128 @@
+/// Batch 2-opt engine: apply many independent moves from one evaluation
+///
+/// All moves `(i, j)` of a round are evaluated against the same tour (in
+/// parallel), so after one of them is applied the gains of the others are only
+/// still valid if the moves do not interact. A move reverses `tour[i..=j]`: it
+/// removes the edges at positions `i - 1` and `j` (edge `p` joins `tour[p]` and
+/// `tour[p + 1]`) and adds edges from `tour[i - 1]` to `tour[j]` and from
+/// `tour[i]` to `tour[j + 1]`. Two moves are independent when
+///
+/// - they remove different edges, and
+/// - their intervals are disjoint or nested, never crossing. A crossing move
+///   would reverse one endpoint of the other move's added edges and not the
+///   other, so those edges would join different cities once both are applied.
+///
+/// `select_independent` takes the best moves greedily under these two rules
+/// (the `par_topk` flags only kept disjoint intervals, and treated intervals
+/// sharing an edge as disjoint; the `mult*` variants marked only the four
+/// endpoints and let crossing moves through). Each position remembers the
+/// innermost accepted interval containing it, so a candidate crosses nothing
+/// exactly when both of its endpoints lie in the same innermost interval.
+///
+/// `apply_batch` applies the moves innermost first: an inner reversal leaves
+/// the endpoints of every enclosing move in place, so all gains add up. Moves of
+/// one nesting depth are disjoint slices and are reversed in parallel. Debug
+/// builds check that the result is a permutation and that its length dropped
+/// by exactly the summed gains.
+use rayon::prelude::*;
+use crate::all_versions::neighborhoods::two_opt_gains;
+use crate::all_versions::utils::*;
+
+/// No accepted interval contains the position
+const OUTSIDE: usize = usize::MAX;
+
+/// A selected 2-opt move: reverse `tour[i..=j]`, saving `gain`
+#[derive(Clone, Copy, Debug)]
+pub struct BatchMove {
+    pub gain: f64,
+    pub i: usize,
+    pub j: usize,
+}
+
+/// Greedily pick up to `max_moves` mutually independent moves from
+/// `candidates` (`(gain, i, j)` with `1 <= i < j < n`), best gain first
+pub fn select_independent(n: usize, candidates: &mut [(f64, usize, usize)], max_moves: usize) -> Vec<BatchMove> {
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let mut removed = vec![false; n];
+    let mut innermost = vec![OUTSIDE; n];
+    let mut selected: Vec<BatchMove> = Vec::new();
+    for &(gain, i, j) in candidates.iter() {
+        if selected.len() >= max_moves {
+            break;
+        }
+        debug_assert!(1 <= i && i < j && j < n);
+        if removed[i - 1] || removed[j] || innermost[i] != innermost[j] {
+            continue;
+        }
+        let parent = innermost[i];
+        let id = selected.len();
+        for owner in &mut innermost[i..=j] {
+            if *owner == parent {
+                *owner = id;
+            }
+        }
+        removed[i - 1] = true;
+        removed[j] = true;
+        selected.push(BatchMove { gain, i, j });
+    }
+    selected
+}
+
+/// Apply independent `moves` (from `select_independent` on this tour),
+/// innermost first and each depth in parallel; returns the summed gain
+pub fn apply_batch(tour: &mut [usize], cities: &[City], moves: &[BatchMove]) -> f64 {
+    let before = if cfg!(debug_assertions) { compute_total_distance(tour, cities) } else { 0.0 };
+
+    // Nesting depth of every move: outer intervals sort before inner ones
+    let mut order: Vec<(usize, &BatchMove)> = moves.iter().map(|m| (0, m)).collect();
+    order.sort_by_key(|&(_, m)| (m.i, std::cmp::Reverse(m.j)));
+    let mut open: Vec<usize> = Vec::new();
+    for (depth, m) in order.iter_mut() {
+        while open.last().is_some_and(|&j| j < m.i) {
+            open.pop();
+        }
+        *depth = open.len();
+        open.push(m.j);
+    }
+    order.sort_by_key(|&(depth, m)| (std::cmp::Reverse(depth), m.i));
+    for level in order.chunk_by(|a, b| a.0 == b.0) {
+        let mut slices = Vec::with_capacity(level.len());
+        let mut rest = &mut *tour;
+        let mut offset = 0;
+        for (_, m) in level {
+            let (_, tail) = rest.split_at_mut(m.i - offset);
+            let (segment, tail) = tail.split_at_mut(m.j + 1 - m.i);
+            slices.push(segment);
+            rest = tail;
+            offset = m.j + 1;
+        }
+        slices.par_iter_mut().for_each(|segment| segment.reverse());
+    }
+
+    let gain: f64 = moves.iter().map(|m| m.gain).sum();
+    if cfg!(debug_assertions) {
+        let mut seen = vec![false; tour.len()];
+        debug_assert!(tour.iter().all(|&c| c < seen.len() && !std::mem::replace(&mut seen[c], true)), "batch broke the permutation");
+        let after = compute_total_distance(tour, cities);
+        debug_assert!(
+            (before - after - gain).abs() <= 1e-6 * before.max(1.0),
+            "batch gain {} but the tour shrank by {}",
+            gain,
+            before - after
+        );
+    }
+    gain
+}
+
+/// One batch round on `tour`: evaluate every move of `rows` in parallel, keep
+/// gains above `min_gain`, apply up to `max_moves` independent ones. Returns
+/// the number of moves applied and the length saved.
+pub fn batch_two_opt_round(tour: &mut [usize], cities: &[City], rows: &[usize], min_gain: f64, max_moves: usize) -> (usize, f64) {
+    let mut candidates: Vec<(f64, usize, usize)> = two_opt_gains(tour, cities, rows)
+        .into_par_iter()
+        .filter(|&(gain, _, _)| gain > min_gain)
+        .collect();
+    let moves = select_independent(tour.len(), &mut candidates, max_moves);
+    let saved = apply_batch(tour, cities, &moves);
+    (moves.len(), saved)
+}
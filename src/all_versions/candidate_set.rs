@@ -0,0 +1,477 @@
+/// Candidate sets for neighbourhood-restricted local search
+///
+/// A `CandidateSet` holds, for every city, the cities it may be joined to by a
+/// move, best first. It dereferences to `[Vec<usize>]`, the shape every
+/// restricted search (`local_search`, ILS, SA, GLS, the memetic GA) consumes.
+/// `Candidates` selects the generator from the configs:
+///
+/// - `Nearest(k)`: the `k` nearest cities (`local_search::neighbor_lists`)
+/// - `Quadrant(k)`: the `k` nearest cities in each of the four quadrants
+///   around the city, closest first. On clustered instances this keeps links
+///   to the neighbouring clusters that plain nearest lists lose.
+/// - `Delaunay`: the neighbours in the Delaunay triangulation (~6 per city),
+///   built by Bowyer–Watson insertion in Hilbert-curve order
+/// - `Alpha(k)`: LKH's alpha-nearness. `alpha(i, j)` is how much the minimum
+///   1-tree (spanning tree of cities 1..n plus the two cheapest edges of city
+///   0) grows when edge `(i, j)` is forced into it, on edge costs
+///   shifted by the Held–Karp penalties of `lower_bound`. The `k` cities of
+///   smallest alpha are kept. This is O(n²) per subgradient iteration plus
+///   O(n²) for the alpha values (parallel over cities), so it suits a few
+///   thousand cities at most.
+///
+/// A city left without candidates (duplicate points the triangulation could
+/// not place) falls back to its nearest neighbours.
+use rayon::prelude::*;
+use std::ops::Deref;
+use crate::all_versions::construction::space_filling_curve_tour;
+use crate::all_versions::local_search::neighbor_lists;
+use crate::all_versions::lower_bound::*;
+use crate::all_versions::spatial_index::KdTree;
+use crate::all_versions::utils::*;
+
+/// Candidates given to a city left without any
+const FALLBACK_K: usize = 6;
+
+const NONE: usize = usize::MAX;
+
+/// Candidate set generator
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Candidates {
+    Nearest(usize),
+    Quadrant(usize),
+    Delaunay,
+    Alpha(usize),
+}
+
+#[allow(dead_code)]
+impl Candidates {
+    pub fn name(&self) -> String {
+        match self {
+            Candidates::Nearest(k) => format!("nearest({})", k),
+            Candidates::Quadrant(k) => format!("quadrant({})", k),
+            Candidates::Delaunay => "delaunay".to_string(),
+            Candidates::Alpha(k) => format!("alpha({})", k),
+        }
+    }
+
+    pub fn build(&self, cities: &[City]) -> CandidateSet {
+        match *self {
+            Candidates::Nearest(k) => CandidateSet::nearest(cities, k),
+            Candidates::Quadrant(k) => CandidateSet::quadrant(cities, k),
+            Candidates::Delaunay => CandidateSet::delaunay(cities),
+            Candidates::Alpha(k) => CandidateSet::alpha_nearness(cities, k, &LowerBoundConfig::default()),
+        }
+    }
+}
+
+/// Per-city candidate lists, best first
+#[derive(Clone, Debug)]
+pub struct CandidateSet {
+    lists: Vec<Vec<usize>>,
+}
+
+impl Deref for CandidateSet {
+    type Target = [Vec<usize>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.lists
+    }
+}
+
+#[allow(dead_code)]
+impl CandidateSet {
+    /// Wrap precomputed lists; cities without candidates get their nearest ones
+    pub fn from_lists(cities: &[City], mut lists: Vec<Vec<usize>>) -> Self {
+        assert_eq!(lists.len(), cities.len(), "one candidate list per city");
+        if cities.len() > 1 && lists.iter().any(|l| l.is_empty()) {
+            let tree = KdTree::new(cities);
+            for (a, list) in lists.iter_mut().enumerate().filter(|(_, l)| l.is_empty()) {
+                *list = tree.k_nearest_where(&cities[a], FALLBACK_K, |b| b != a);
+            }
+        }
+        Self { lists }
+    }
+
+    pub fn nearest(cities: &[City], k: usize) -> Self {
+        Self::from_lists(cities, neighbor_lists(cities, k))
+    }
+
+    pub fn quadrant(cities: &[City], k: usize) -> Self {
+        let tree = KdTree::new(cities);
+        let lists = (0..cities.len())
+            .into_par_iter()
+            .map(|a| {
+                let mut list: Vec<usize> = (0..4)
+                    .flat_map(|q| tree.k_nearest_where(&cities[a], k, |b| quadrant(&cities[a], &cities[b]) == Some(q)))
+                    .collect();
+                sort_by_distance(&mut list, a, cities);
+                list
+            })
+            .collect();
+        Self::from_lists(cities, lists)
+    }
+
+    pub fn delaunay(cities: &[City]) -> Self {
+        let mut lists = Triangulation::new(cities).neighbors();
+        lists.par_iter_mut().enumerate().for_each(|(a, list)| sort_by_distance(list, a, cities));
+        Self::from_lists(cities, lists)
+    }
+
+    /// `k` alpha-nearest cities under the penalties of a `config` bound
+    pub fn alpha_nearness(cities: &[City], k: usize, config: &LowerBoundConfig) -> Self {
+        let pi = held_karp_bound(cities, config).penalties;
+        Self::from_lists(cities, alpha_lists(cities, k, &pi))
+    }
+
+    pub fn neighbors(&self, city: usize) -> &[usize] {
+        &self.lists[city]
+    }
+
+    /// Lists with the candidates of `other` appended (duplicates dropped)
+    pub fn union(&self, other: &CandidateSet) -> Self {
+        let lists = self
+            .lists
+            .iter()
+            .zip(&other.lists)
+            .map(|(a, b)| {
+                let mut list = a.clone();
+                list.extend(b.iter().filter(|c| !a.contains(c)));
+                list
+            })
+            .collect();
+        Self { lists }
+    }
+
+    /// Mean list length
+    pub fn average_size(&self) -> f64 {
+        self.lists.iter().map(Vec::len).sum::<usize>() as f64 / self.lists.len().max(1) as f64
+    }
+
+    pub fn into_lists(self) -> Vec<Vec<usize>> {
+        self.lists
+    }
+}
+
+fn sort_by_distance(list: &mut Vec<usize>, a: usize, cities: &[City]) {
+    list.sort_by(|&b, &c| {
+        euclidean_distance(&cities[a], &cities[b])
+            .total_cmp(&euclidean_distance(&cities[a], &cities[c]))
+            .then(b.cmp(&c))
+    });
+    list.dedup();
+}
+
+/// Quadrant of `b` around `a` (half-open, so every other point is in exactly
+/// one); `None` for a point at the same position
+fn quadrant(a: &City, b: &City) -> Option<usize> {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    if dx > 0.0 && dy >= 0.0 {
+        Some(0)
+    } else if dx <= 0.0 && dy > 0.0 {
+        Some(1)
+    } else if dx < 0.0 && dy <= 0.0 {
+        Some(2)
+    } else if dx >= 0.0 && dy < 0.0 {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+/// Twice the signed area of `a b c` (> 0 when counter-clockwise)
+fn orient(a: &City, b: &City, c: &City) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+/// True if `p` lies inside the circumcircle of the counter-clockwise `a b c`
+fn in_circle(a: &City, b: &City, c: &City, p: &City) -> bool {
+    let (adx, ady) = (a.x - p.x, a.y - p.y);
+    let (bdx, bdy) = (b.x - p.x, b.y - p.y);
+    let (cdx, cdy) = (c.x - p.x, c.y - p.y);
+    let det = (adx * adx + ady * ady) * (bdx * cdy - bdy * cdx)
+        + (bdx * bdx + bdy * bdy) * (cdx * ady - cdy * adx)
+        + (cdx * cdx + cdy * cdy) * (adx * bdy - ady * bdx);
+    det > 0.0
+}
+
+/// Incremental Delaunay triangulation (Bowyer–Watson). Vertices `n..n + 3`
+/// form a super-triangle around all cities; triangles are counter-clockwise
+/// and `adj[t][i]` is the triangle across the edge opposite `tri[t][i]`.
+struct Triangulation {
+    points: Vec<City>,
+    n: usize,
+    tri: Vec<[usize; 3]>,
+    adj: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+    /// Scratch for linking the triangles of one insertion: the new triangle
+    /// whose boundary edge starts at a vertex
+    from: Vec<usize>,
+    /// `twin[p]`: the vertex at the same position as city `p`, which was not inserted
+    twin: Vec<usize>,
+}
+
+impl Triangulation {
+    fn new(cities: &[City]) -> Self {
+        let n = cities.len();
+        let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::INFINITY, f64::INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for c in cities {
+            min_x = min_x.min(c.x);
+            min_y = min_y.min(c.y);
+            max_x = max_x.max(c.x);
+            max_y = max_y.max(c.y);
+        }
+        let mut points = cities.to_vec();
+        if n > 0 {
+            // large enough to miss few hull edges, small enough for the
+            // in-circle test to stay accurate
+            let (cx, cy) = ((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+            let s = (max_x - min_x).max(max_y - min_y).max(1.0) * 100.0;
+            points.push(City { x: cx - s, y: cy - s });
+            points.push(City { x: cx + s, y: cy - s });
+            points.push(City { x: cx, y: cy + s });
+        }
+        let mut t = Self {
+            points,
+            n,
+            tri: vec![[n, n + 1, n + 2]],
+            adj: vec![[NONE; 3]],
+            alive: vec![true],
+            free: Vec::new(),
+            from: vec![NONE; n + 3],
+            twin: vec![NONE; n],
+        };
+        if n > 0 {
+            let mut last = 0;
+            for p in space_filling_curve_tour(cities) {
+                last = t.insert(p, last);
+            }
+        }
+        t
+    }
+
+    /// Triangle containing point `p`, walking from `start`
+    fn locate(&self, p: usize, start: usize) -> Option<usize> {
+        let pts = &self.points;
+        let mut t = start;
+        'walk: for _ in 0..self.tri.len() {
+            for i in 0..3 {
+                let (a, b) = (self.tri[t][(i + 1) % 3], self.tri[t][(i + 2) % 3]);
+                if orient(&pts[a], &pts[b], &pts[p]) < 0.0 {
+                    match self.adj[t][i] {
+                        NONE => break 'walk,
+                        next => {
+                            t = next;
+                            continue 'walk;
+                        }
+                    }
+                }
+            }
+            return Some(t);
+        }
+        // the walk cycled on nearly degenerate triangles
+        (0..self.tri.len()).find(|&t| {
+            self.alive[t] && (0..3).all(|i| orient(&pts[self.tri[t][(i + 1) % 3]], &pts[self.tri[t][(i + 2) % 3]], &pts[p]) >= 0.0)
+        })
+    }
+
+    fn add(&mut self, tri: [usize; 3], adj: [usize; 3]) -> usize {
+        if let Some(t) = self.free.pop() {
+            self.tri[t] = tri;
+            self.adj[t] = adj;
+            self.alive[t] = true;
+            t
+        } else {
+            self.tri.push(tri);
+            self.adj.push(adj);
+            self.alive.push(true);
+            self.tri.len() - 1
+        }
+    }
+
+    /// Insert city `p`, starting the search at triangle `hint`; returns a
+    /// triangle next to `p` to start the next search from
+    fn insert(&mut self, p: usize, hint: usize) -> usize {
+        let hint = if self.alive[hint] { hint } else { self.alive.iter().position(|&a| a).unwrap() };
+        let Some(t0) = self.locate(p, hint) else {
+            return hint;
+        };
+        let pts = &self.points;
+        if let Some(&v) = self.tri[t0].iter().find(|&&v| pts[v].x == pts[p].x && pts[v].y == pts[p].y) {
+            self.twin[p] = v;
+            return t0;
+        }
+
+        // Cavity: the triangles whose circumcircle contains p, and its boundary
+        // edges (a, b) with the triangle outside them
+        let mut cavity = vec![t0];
+        let mut boundary = Vec::new();
+        self.alive[t0] = false;
+        let mut k = 0;
+        while k < cavity.len() {
+            let t = cavity[k];
+            k += 1;
+            for i in 0..3 {
+                let out = self.adj[t][i];
+                if out != NONE && self.alive[out] {
+                    let [a, b, c] = self.tri[out];
+                    if in_circle(&self.points[a], &self.points[b], &self.points[c], &self.points[p]) {
+                        self.alive[out] = false;
+                        cavity.push(out);
+                        continue;
+                    }
+                }
+                if out == NONE || self.alive[out] {
+                    boundary.push((self.tri[t][(i + 1) % 3], self.tri[t][(i + 2) % 3], out));
+                }
+            }
+        }
+        self.free.extend(cavity);
+
+        // Fan the boundary around p
+        let mut created = Vec::with_capacity(boundary.len());
+        for &(a, b, out) in &boundary {
+            let t = self.add([a, b, p], [NONE, NONE, out]);
+            if out != NONE {
+                if let Some(j) = (0..3).find(|&j| self.tri[out][(j + 1) % 3] == b && self.tri[out][(j + 2) % 3] == a) {
+                    self.adj[out][j] = t;
+                }
+            }
+            self.from[a] = t;
+            created.push(t);
+        }
+        for &t in &created {
+            let next = self.from[self.tri[t][1]];
+            self.adj[t][0] = next;
+            if next != NONE {
+                self.adj[next][1] = t;
+            }
+        }
+        created[0]
+    }
+
+    /// Delaunay neighbours of every city (unsorted)
+    fn neighbors(&self) -> Vec<Vec<usize>> {
+        let n = self.n;
+        let mut lists = vec![Vec::new(); n];
+        for t in (0..self.tri.len()).filter(|&t| self.alive[t]) {
+            for i in 0..3 {
+                let (a, b) = (self.tri[t][i], self.tri[t][(i + 1) % 3]);
+                if a < n && b < n {
+                    lists[a].push(b);
+                    lists[b].push(a);
+                }
+            }
+        }
+        for list in &mut lists {
+            list.sort_unstable();
+            list.dedup();
+        }
+        for p in (0..n).filter(|&p| self.twin[p] != NONE) {
+            let v = self.twin[p];
+            let mut list = lists[v].clone();
+            list.push(v);
+            lists[p] = list;
+            lists[v].push(p);
+        }
+        lists
+    }
+}
+
+/// `k` alpha-nearest cities of every city under penalties `pi`
+fn alpha_lists(cities: &[City], k: usize, pi: &[f64]) -> Vec<Vec<usize>> {
+    let n = cities.len();
+    let k = k.min(n.saturating_sub(1));
+    if k == 0 {
+        return vec![Vec::new(); n];
+    }
+    let c = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]) + pi[a] + pi[b];
+
+    // Minimum 1-tree as in LKH: a minimum spanning tree of cities 1..n, by Prim
+    // from city 1 (`order` lists them parents first, `dad` is the parent, NONE
+    // at the root), plus the two cheapest edges of city 0. An edge (0, j) can
+    // only replace the second cheapest of those, so alpha(0, j) = c(0, j) - c2,
+    // and 0 for the two tree edges.
+    let mut dad = vec![NONE; n];
+    let mut key = vec![f64::INFINITY; n];
+    let mut in_tree = vec![false; n];
+    in_tree[0] = true;
+    let mut order = Vec::with_capacity(n - 1);
+    let mut v = 1;
+    for _ in 1..n {
+        in_tree[v] = true;
+        order.push(v);
+        let mut next = (f64::INFINITY, NONE);
+        for u in (0..n).filter(|&u| !in_tree[u]) {
+            let w = c(v, u);
+            if w < key[u] {
+                key[u] = w;
+                dad[u] = v;
+            }
+            if key[u] < next.0 {
+                next = (key[u], u);
+            }
+        }
+        if next.1 == NONE {
+            break;
+        }
+        v = next.1;
+    }
+
+    let mut at_zero: Vec<f64> = (1..n).map(|j| c(0, j)).collect();
+    let second = *at_zero.select_nth_unstable_by(1.min(n - 2), f64::total_cmp).1;
+    let alpha_zero = |j: usize| (c(0, j) - second).max(0.0);
+
+    // For city i, beta[j] is the largest edge on the tree path from i to j,
+    // so alpha(i, j) = c(i, j) - beta[j]. Cities on the path from i to the
+    // root are filled walking up, all others from their parent in `order`.
+    (0..n)
+        .into_par_iter()
+        .map_init(
+            || (vec![0.0; n], vec![NONE; n]),
+            |(beta, mark), i| {
+                let by_alpha = |x: &(f64, f64, usize), y: &(f64, f64, usize)| x.0.total_cmp(&y.0).then(x.1.total_cmp(&y.1));
+                let mut alpha: Vec<(f64, f64, usize)> = Vec::with_capacity(n - 1);
+                if i == 0 {
+                    alpha.extend((1..n).map(|j| (alpha_zero(j), euclidean_distance(&cities[0], &cities[j]), j)));
+                    return nearest_k(alpha, k, by_alpha);
+                }
+                alpha.push((alpha_zero(i), euclidean_distance(&cities[i], &cities[0]), 0));
+                beta[i] = f64::NEG_INFINITY;
+                mark[i] = i;
+                let mut j = i;
+                while dad[j] != NONE {
+                    let d = dad[j];
+                    beta[d] = beta[j].max(c(j, d));
+                    mark[d] = i;
+                    j = d;
+                }
+                for &j in &order {
+                    if j == i {
+                        continue;
+                    }
+                    if mark[j] != i {
+                        beta[j] = beta[dad[j]].max(c(j, dad[j]));
+                    }
+                    alpha.push((c(i, j) - beta[j], euclidean_distance(&cities[i], &cities[j]), j));
+                }
+                nearest_k(alpha, k, by_alpha)
+            },
+        )
+        .collect()
+}
+
+/// Cities of the `k` smallest `(alpha, distance, city)` entries, smallest first
+fn nearest_k(
+    mut alpha: Vec<(f64, f64, usize)>,
+    k: usize,
+    by: impl Fn(&(f64, f64, usize), &(f64, f64, usize)) -> std::cmp::Ordering,
+) -> Vec<usize> {
+    if k < alpha.len() {
+        alpha.select_nth_unstable_by(k, &by);
+        alpha.truncate(k);
+    }
+    alpha.sort_by(by);
+    alpha.into_iter().map(|(_, _, j)| j).collect()
+}
@@ -0,0 +1,692 @@
+/// Construction heuristics: start tours for the local searches and the GAs
+///
+/// - `nearest_neighbor_tour`: greedy walk to the closest unvisited city
+///   (formerly `get_initial_route` in `optimized_ver2_multi2opt`), answered by
+///   `spatial_index::KdTree` with deletions instead of a linear scan
+/// - `insertion_tour`: cheapest-position insertion of cities in a given order
+///   (formerly `get_initial_route` in `random_insert_ver3_multi2opt`), built on
+///   `cheapest_insertion`, which LNS also uses to recreate ruined tours
+/// - `greedy_edge_tour`: shortest candidate edges first, keeping degrees <= 2
+///   and no subtours; the resulting fragments are chained nearest-end first
+/// - `nearest_insertion_tour` / `farthest_insertion_tour` /
+///   `cheapest_insertion_tour`: grow a tour from one city, choosing the next
+///   city by distance to the tour or by insertion cost, and inserting it at
+///   its cheapest position
+/// - `convex_hull_tour`: cheapest insertion starting from the convex hull
+/// - `space_filling_curve_tour`: cities in Hilbert-curve order
+/// - `double_tree_tour`: preorder walk of the minimum spanning tree (the
+///   shortcut doubled tree, at most twice the optimum)
+/// - `christofides_tour`: MST plus a minimum-weight perfect matching of its
+///   odd-degree cities (`matching`), Euler circuit, shortcuts; at most 3/2 of
+///   the optimum, O(n³)
+/// - `mst_greedy_matching_tour`: the same with a greedy matching followed by
+///   pairwise exchanges, which scales to large n but loses the 3/2 guarantee
+/// - `Construction`: all of the above (and random tours) selectable by name
+/// - `seeded_population`: GA initial population with a fraction built from
+///   nearest neighbour / random insertion, perturbed with double-bridge kicks
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+use crate::all_versions::local_search::neighbor_lists;
+use crate::all_versions::matching::min_weight_perfect_matching;
+use crate::all_versions::spatial_index::KdTree;
+use crate::all_versions::utils::*;
+
+/// Candidate edges per city for `greedy_edge_tour`
+const GREEDY_CANDIDATES: usize = 10;
+/// Largest odd-degree city count `odd_matching` pairs over all O(m²) pairs
+const MATCHING_ALL_PAIRS_MAX: usize = 2000;
+/// Nearest odd-degree cities a city may be matched to above `MATCHING_ALL_PAIRS_MAX`
+const MATCHING_CANDIDATES: usize = 10;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Construction {
+    Random,
+    NearestNeighbor,
+    GreedyEdge,
+    NearestInsertion,
+    FarthestInsertion,
+    CheapestInsertion,
+    RandomInsertion,
+    ConvexHull,
+    SpaceFillingCurve,
+    Christofides,
+    MstMatching,
+    DoubleTree,
+}
+
+impl Construction {
+    #[allow(dead_code)]
+    pub const ALL: [Construction; 12] = [
+        Construction::Random,
+        Construction::NearestNeighbor,
+        Construction::GreedyEdge,
+        Construction::NearestInsertion,
+        Construction::FarthestInsertion,
+        Construction::CheapestInsertion,
+        Construction::RandomInsertion,
+        Construction::ConvexHull,
+        Construction::SpaceFillingCurve,
+        Construction::Christofides,
+        Construction::MstMatching,
+        Construction::DoubleTree,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Construction::Random => "random",
+            Construction::NearestNeighbor => "nn",
+            Construction::GreedyEdge => "greedy",
+            Construction::NearestInsertion => "nearest",
+            Construction::FarthestInsertion => "farthest",
+            Construction::CheapestInsertion => "cheapest",
+            Construction::RandomInsertion => "insertion",
+            Construction::ConvexHull => "hull",
+            Construction::SpaceFillingCurve => "hilbert",
+            Construction::Christofides => "christofides",
+            Construction::MstMatching => "mst-matching",
+            Construction::DoubleTree => "mst",
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.name() == name)
+    }
+
+    /// Build a start tour; `seed` drives the randomised constructions
+    #[allow(dead_code)]
+    pub fn build(&self, cities: &[City], seed: u64) -> Vec<usize> {
+        let n = cities.len();
+        let mut rng = stream_rng(seed, 0, 0);
+        let mut shuffled: Vec<usize> = (0..n).collect();
+        shuffled.shuffle(&mut rng);
+        if n < 3 {
+            return shuffled;
+        }
+        match self {
+            Construction::Random => shuffled,
+            Construction::NearestNeighbor => nearest_neighbor_tour(rng.gen_range(0..n), cities),
+            Construction::GreedyEdge => greedy_edge_tour(cities),
+            Construction::NearestInsertion => nearest_insertion_tour(cities),
+            Construction::FarthestInsertion => farthest_insertion_tour(cities),
+            Construction::CheapestInsertion => cheapest_insertion_tour(cities),
+            Construction::RandomInsertion => insertion_tour(&shuffled, cities),
+            Construction::ConvexHull => convex_hull_tour(cities),
+            Construction::SpaceFillingCurve => space_filling_curve_tour(cities),
+            Construction::Christofides => christofides_tour(cities),
+            Construction::MstMatching => mst_greedy_matching_tour(cities),
+            Construction::DoubleTree => double_tree_tour(cities),
+        }
+    }
+}
+
+fn dist(cities: &[City], a: usize, b: usize) -> f64 {
+    euclidean_distance(&cities[a], &cities[b])
+}
+
+/// Greedy nearest-neighbour tour starting from `start_point`
+pub fn nearest_neighbor_tour(start_point: usize, cities: &[City]) -> Vec<usize> {
+    let n = cities.len();
+    let mut route = Vec::with_capacity(n);
+    let mut tree = KdTree::new(cities);
+    route.push(start_point);
+    tree.remove(start_point);
+    let mut prev = start_point;
+
+    // the nearest unvisited city to the previously added one
+    while let Some(nxt) = tree.nearest(prev) {
+        route.push(nxt);
+        tree.remove(nxt);
+        prev = nxt;
+    }
+    route
+}
+
+/// Insert cities one by one, in the order given by `order`, at the position of
+/// the closed tour where they add the least length. The first `max(n / 100, 2)`
+/// cities of `order` form the starting sub-tour, so a shuffled `order` gives a
+/// different (random insertion) tour per call.
+///
+/// This replaces `mult4`'s original builder, which treated the route as an open
+/// path (inserting before the first or after the last city cost one edge) and
+/// inserted from `order[n / 100..]` after seeding with `max(n / 100, 2)`
+/// cities, so below 200 cities one or two cities appeared twice.
+pub fn insertion_tour(order: &[usize], cities: &[City]) -> Vec<usize> {
+    let n = order.len();
+    let seed_size = (n / 100).max(2).min(n);
+    let mut route: Vec<usize> = order[..seed_size].to_vec();
+
+    for &city in &order[seed_size..] {
+        let (best_pos, _) = cheapest_insertion(&route, city, cities);
+        route.insert(best_pos, city);
+    }
+    route
+}
+
+/// Cheapest place to insert `city` into the closed tour `route`: returns the
+/// index to `insert` at and the added length. Inserting at `i + 1` places the
+/// city between `route[i]` and `route[i + 1]`.
+pub fn cheapest_insertion(route: &[usize], city: usize, cities: &[City]) -> (usize, f64) {
+    let mut best_pos = 0;
+    let mut lowest_cost = f64::INFINITY;
+
+    for i in 0..route.len() {
+        let prev = route[i];
+        let next = route[(i + 1) % route.len()];
+        let new_cost = euclidean_distance(&cities[prev], &cities[city])
+            + euclidean_distance(&cities[city], &cities[next])
+            - euclidean_distance(&cities[prev], &cities[next]);
+
+        if new_cost < lowest_cost {
+            lowest_cost = new_cost;
+            best_pos = i + 1;
+        }
+    }
+    (best_pos, lowest_cost)
+}
+
+/// Greedy edge matching: add candidate edges shortest first unless they give a
+/// city degree 3 or close a cycle, then chain the path fragments by walking to
+/// the nearest free fragment end
+#[allow(dead_code)]
+pub fn greedy_edge_tour(cities: &[City]) -> Vec<usize> {
+    let n = cities.len();
+    let mut edges: Vec<(f64, usize, usize)> = neighbor_lists(cities, GREEDY_CANDIDATES)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(a, nbs)| nbs.into_iter().filter(move |&b| a < b).map(move |b| (a, b)))
+        .map(|(a, b)| (dist(cities, a, b), a, b))
+        .collect();
+    edges.par_sort_unstable_by(|x, y| x.partial_cmp(y).unwrap());
+
+    let mut parent: Vec<usize> = (0..n).collect();
+    fn find(parent: &mut [usize], mut v: usize) -> usize {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+    let mut adj: Vec<Vec<usize>> = vec![Vec::with_capacity(2); n];
+    for (_, a, b) in edges {
+        if adj[a].len() < 2 && adj[b].len() < 2 {
+            let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+            if ra != rb {
+                parent[ra] = rb;
+                adj[a].push(b);
+                adj[b].push(a);
+            }
+        }
+    }
+
+    // Fragment ends (a single city is both ends of its fragment)
+    let ends: Vec<usize> = (0..n).filter(|&v| adj[v].len() < 2).collect();
+    let mut used = vec![false; n];
+    let mut route = Vec::with_capacity(n);
+    let mut current = ends[0];
+    loop {
+        // walk the fragment from `current` to its other end
+        let mut prev = usize::MAX;
+        let mut v = current;
+        loop {
+            used[v] = true;
+            route.push(v);
+            match adj[v].iter().copied().find(|&w| w != prev && !used[w]) {
+                Some(w) => {
+                    prev = v;
+                    v = w;
+                }
+                None => break,
+            }
+        }
+        let next = ends
+            .iter()
+            .copied()
+            .filter(|&e| !used[e])
+            .min_by(|&a, &b| dist(cities, v, a).partial_cmp(&dist(cities, v, b)).unwrap());
+        match next {
+            Some(e) => current = e,
+            None => break,
+        }
+    }
+    route
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pick {
+    /// Closest city to the tour
+    Nearest,
+    /// Farthest city from the tour
+    Farthest,
+    /// City with the cheapest insertion
+    Cheapest,
+}
+
+/// Grow the closed tour `initial` by insertion, choosing cities by `pick` and
+/// inserting each at its cheapest position. The tour is a successor list; every
+/// remaining city keeps its best insertion edge `(cost, a)` = between `a` and
+/// `next[a]`, recomputed in full only when that edge is the one split. The
+/// updates after each insertion run in parallel over the remaining cities.
+fn insertion(cities: &[City], initial: &[usize], pick: Pick) -> Vec<usize> {
+    let n = cities.len();
+    let mut next = vec![usize::MAX; n];
+    for (i, &c) in initial.iter().enumerate() {
+        next[c] = initial[(i + 1) % initial.len()];
+    }
+    let d = |a: usize, b: usize| dist(cities, a, b);
+    let cost_at = |next: &[usize], u: usize, a: usize| d(a, u) + d(u, next[a]) - d(a, next[a]);
+    let full_best = |next: &[usize], u: usize| {
+        let mut best = (f64::INFINITY, initial[0]);
+        let mut a = initial[0];
+        loop {
+            let c = cost_at(next, u, a);
+            if c < best.0 {
+                best = (c, a);
+            }
+            a = next[a];
+            if a == initial[0] {
+                return best;
+            }
+        }
+    };
+
+    // (city, best insertion, distance to the tour) of every city not in the tour
+    let mut remaining: Vec<(usize, (f64, usize), f64)> = (0..n)
+        .into_par_iter()
+        .filter(|&u| next[u] == usize::MAX)
+        .map(|u| (u, full_best(&next, u), initial.iter().map(|&c| d(u, c)).fold(f64::INFINITY, f64::min)))
+        .collect();
+
+    while !remaining.is_empty() {
+        let key = |&(_, best, to_tour): &(usize, (f64, usize), f64)| match pick {
+            Pick::Nearest => to_tour,
+            Pick::Farthest => -to_tour,
+            Pick::Cheapest => best.0,
+        };
+        let (r, _) = remaining
+            .iter()
+            .enumerate()
+            .min_by(|x, y| key(x.1).partial_cmp(&key(y.1)).unwrap())
+            .unwrap();
+        let (u, (_, a), _) = remaining.swap_remove(r);
+        let b = next[a];
+        next[a] = u;
+        next[u] = b;
+
+        let next = &next;
+        remaining.par_iter_mut().with_min_len(1024).for_each(|(v, best, to_tour)| {
+            *to_tour = to_tour.min(d(*v, u));
+            if best.1 == a {
+                *best = full_best(next, *v);
+            } else {
+                for e in [a, u] {
+                    let c = cost_at(next, *v, e);
+                    if c < best.0 {
+                        *best = (c, e);
+                    }
+                }
+            }
+        });
+    }
+
+    let mut route = Vec::with_capacity(n);
+    let mut c = initial[0];
+    for _ in 0..n {
+        route.push(c);
+        c = next[c];
+    }
+    route
+}
+
+/// Nearest insertion from city 0
+#[allow(dead_code)]
+pub fn nearest_insertion_tour(cities: &[City]) -> Vec<usize> {
+    insertion(cities, &[0], Pick::Nearest)
+}
+
+/// Farthest insertion from city 0
+#[allow(dead_code)]
+pub fn farthest_insertion_tour(cities: &[City]) -> Vec<usize> {
+    insertion(cities, &[0], Pick::Farthest)
+}
+
+/// Cheapest insertion from city 0
+#[allow(dead_code)]
+pub fn cheapest_insertion_tour(cities: &[City]) -> Vec<usize> {
+    insertion(cities, &[0], Pick::Cheapest)
+}
+
+/// Convex hull in counter-clockwise order (Andrew's monotone chain)
+#[allow(dead_code)]
+pub fn convex_hull(cities: &[City]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..cities.len()).collect();
+    order.sort_by(|&a, &b| (cities[a].x, cities[a].y).partial_cmp(&(cities[b].x, cities[b].y)).unwrap());
+    if order.len() < 3 {
+        return order;
+    }
+    let cross = |o: usize, a: usize, b: usize| {
+        (cities[a].x - cities[o].x) * (cities[b].y - cities[o].y) - (cities[a].y - cities[o].y) * (cities[b].x - cities[o].x)
+    };
+    let mut hull: Vec<usize> = Vec::with_capacity(2 * order.len());
+    for pass in [order.clone(), order.into_iter().rev().collect()] {
+        let floor = hull.len();
+        for c in pass {
+            while hull.len() >= floor + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], c) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(c);
+        }
+        hull.pop();
+    }
+    hull
+}
+
+/// Cheapest insertion starting from the convex hull
+#[allow(dead_code)]
+pub fn convex_hull_tour(cities: &[City]) -> Vec<usize> {
+    let hull = convex_hull(cities);
+    if hull.len() < 3 {
+        return cheapest_insertion_tour(cities);
+    }
+    insertion(cities, &hull, Pick::Cheapest)
+}
+
+/// Index of (x, y) along a Hilbert curve filling a 2^order x 2^order grid
+fn hilbert_index(order: u32, mut x: u64, mut y: u64) -> u64 {
+    let full = (1u64 << order) - 1;
+    let mut d = 0;
+    let mut s = 1u64 << (order - 1);
+    while s > 0 {
+        let rx = ((x & s) > 0) as u64;
+        let ry = ((y & s) > 0) as u64;
+        d += s * s * ((3 * rx) ^ ry);
+        // rotate the quadrant so the curve stays continuous
+        if ry == 0 {
+            if rx == 1 {
+                x = full - x;
+                y = full - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+        s >>= 1;
+    }
+    d
+}
+
+/// Cities sorted along a Hilbert curve over their bounding box
+#[allow(dead_code)]
+pub fn space_filling_curve_tour(cities: &[City]) -> Vec<usize> {
+    const ORDER: u32 = 16;
+    let side = ((1u64 << ORDER) - 1) as f64;
+    let (min_x, max_x) = cities.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c.x), hi.max(c.x)));
+    let (min_y, max_y) = cities.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| (lo.min(c.y), hi.max(c.y)));
+    let extent = (max_x - min_x).max(max_y - min_y).max(f64::MIN_POSITIVE);
+    let mut keyed: Vec<(u64, usize)> = cities
+        .par_iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let x = ((c.x - min_x) / extent * side) as u64;
+            let y = ((c.y - min_y) / extent * side) as u64;
+            (hilbert_index(ORDER, x, y), i)
+        })
+        .collect();
+    keyed.par_sort_unstable();
+    keyed.into_iter().map(|(_, i)| i).collect()
+}
+
+/// Minimum spanning tree (dense Prim) as adjacency lists
+#[allow(dead_code)]
+pub fn minimum_spanning_tree(cities: &[City]) -> Vec<Vec<usize>> {
+    let n = cities.len();
+    let mut adj = vec![Vec::new(); n];
+    if n == 0 {
+        return adj;
+    }
+    let mut in_tree = vec![false; n];
+    let mut key = vec![f64::INFINITY; n];
+    let mut from = vec![0; n];
+    let mut v = 0;
+    for _ in 1..n {
+        in_tree[v] = true;
+        let mut next = (f64::INFINITY, usize::MAX);
+        for u in 0..n {
+            if !in_tree[u] {
+                let c = dist(cities, v, u);
+                if c < key[u] {
+                    key[u] = c;
+                    from[u] = v;
+                }
+                if key[u] < next.0 {
+                    next = (key[u], u);
+                }
+            }
+        }
+        let u = next.1;
+        adj[u].push(from[u]);
+        adj[from[u]].push(u);
+        v = u;
+    }
+    adj
+}
+
+/// Preorder walk of the minimum spanning tree from city 0
+#[allow(dead_code)]
+pub fn double_tree_tour(cities: &[City]) -> Vec<usize> {
+    let adj = minimum_spanning_tree(cities);
+    let mut seen = vec![false; cities.len()];
+    let mut route = Vec::with_capacity(cities.len());
+    let mut stack = vec![0];
+    while let Some(v) = stack.pop() {
+        if !std::mem::replace(&mut seen[v], true) {
+            route.push(v);
+            stack.extend(adj[v].iter().rev().filter(|&&w| !seen[w]));
+        }
+    }
+    route
+}
+
+/// Perfect matching of `odd` (even count): greedy on sorted pair lengths, then
+/// exchanges between matched pairs while any pair of pairs gets shorter. Up to
+/// `MATCHING_ALL_PAIRS_MAX` cities every pair is a candidate; above it only each
+/// city's `MATCHING_CANDIDATES` nearest odd cities are (the full pair list needs
+/// 24 bytes per pair, gigabytes beyond ~10k odd cities), the cities they leave
+/// unmatched are paired nearest-first, and exchanges only try candidate pairs.
+fn odd_matching(cities: &[City], odd: &[usize]) -> Vec<(usize, usize)> {
+    let m = odd.len();
+    let candidates: Option<Vec<Vec<usize>>> = (m > MATCHING_ALL_PAIRS_MAX).then(|| {
+        let mut is_odd = vec![false; cities.len()];
+        for &v in odd {
+            is_odd[v] = true;
+        }
+        let tree = KdTree::new(cities);
+        odd.par_iter()
+            .map(|&a| tree.k_nearest_where(&cities[a], MATCHING_CANDIDATES, |c| c != a && is_odd[c]))
+            .collect()
+    });
+    let mut pairs: Vec<(f64, usize, usize)> = match &candidates {
+        None => (0..m)
+            .into_par_iter()
+            .flat_map_iter(|i| (i + 1..m).map(move |j| (odd[i], odd[j])))
+            .map(|(a, b)| (dist(cities, a, b), a, b))
+            .collect(),
+        Some(lists) => odd
+            .par_iter()
+            .zip(lists)
+            .flat_map_iter(|(&a, nbs)| nbs.iter().map(move |&b| (dist(cities, a, b), a.min(b), a.max(b))))
+            .collect(),
+    };
+    pairs.par_sort_unstable_by(|x, y| x.partial_cmp(y).unwrap());
+    let mut matched = vec![false; cities.len()];
+    let mut matching = Vec::with_capacity(m / 2);
+    for (_, a, b) in pairs {
+        if !matched[a] && !matched[b] {
+            matched[a] = true;
+            matched[b] = true;
+            matching.push((a, b));
+        }
+    }
+    if 2 * matching.len() < m {
+        // Only in candidate mode: pair the rest nearest-first
+        let mut tree = KdTree::new(cities);
+        let mut left = vec![false; cities.len()];
+        for &v in odd.iter().filter(|&&v| !matched[v]) {
+            left[v] = true;
+        }
+        for v in (0..cities.len()).filter(|&v| !left[v]) {
+            tree.remove(v);
+        }
+        for &a in odd.iter().filter(|&&v| left[v]) {
+            if tree.contains(a) {
+                tree.remove(a);
+                let b = tree.nearest_to(&cities[a]).expect("odd-degree cities come in pairs");
+                tree.remove(b);
+                matching.push((a, b));
+            }
+        }
+    }
+
+    let d = |a: usize, b: usize| dist(cities, a, b);
+    // Replace matched pairs i and j by the shorter re-pairing of their cities, if any
+    let exchange = |matching: &mut [(usize, usize)], i: usize, j: usize| -> bool {
+        let ((a, b), (c, e)) = (matching[i], matching[j]);
+        let current = d(a, b) + d(c, e);
+        if d(a, c) + d(b, e) < current - 1e-9 {
+            matching[i] = (a, c);
+            matching[j] = (b, e);
+            true
+        } else if d(a, e) + d(b, c) < current - 1e-9 {
+            matching[i] = (a, e);
+            matching[j] = (b, c);
+            true
+        } else {
+            false
+        }
+    };
+    let mut pair_of = vec![usize::MAX; cities.len()];
+    let mut improved = true;
+    let mut passes = 0;
+    while improved && passes < 50 {
+        improved = false;
+        passes += 1;
+        match &candidates {
+            None => {
+                for i in 0..matching.len() {
+                    for j in i + 1..matching.len() {
+                        improved |= exchange(&mut matching, i, j);
+                    }
+                }
+            }
+            Some(lists) => {
+                for (i, &(a, b)) in matching.iter().enumerate() {
+                    pair_of[a] = i;
+                    pair_of[b] = i;
+                }
+                for (k, &x) in odd.iter().enumerate() {
+                    for &c in &lists[k] {
+                        let (i, j) = (pair_of[x], pair_of[c]);
+                        if i != j && exchange(&mut matching, i, j) {
+                            for p in [i, j] {
+                                let (a, b) = matching[p];
+                                pair_of[a] = p;
+                                pair_of[b] = p;
+                            }
+                            improved = true;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    matching
+}
+
+/// Christofides' tour: MST + minimum-weight perfect matching of its odd-degree
+/// cities, Euler circuit of the union, repeated cities skipped. At most 3/2 of
+/// the optimum; the matching is O(m³) in the m odd-degree cities.
+#[allow(dead_code)]
+pub fn christofides_tour(cities: &[City]) -> Vec<usize> {
+    mst_matching_tour(cities, min_weight_perfect_matching)
+}
+
+/// Christofides-style tour with the greedy `odd_matching` instead of a
+/// minimum-weight perfect matching: scales to large n, but the 3/2 guarantee
+/// does not hold.
+#[allow(dead_code)]
+pub fn mst_greedy_matching_tour(cities: &[City]) -> Vec<usize> {
+    mst_matching_tour(cities, odd_matching)
+}
+
+/// Pairs up an even set of cities
+type Matching = fn(&[City], &[usize]) -> Vec<(usize, usize)>;
+
+/// MST + `matching` of its odd-degree cities, Euler circuit of the union
+/// (Hierholzer), repeated cities skipped
+fn mst_matching_tour(cities: &[City], matching: Matching) -> Vec<usize> {
+    let n = cities.len();
+    let tree = minimum_spanning_tree(cities);
+    let odd: Vec<usize> = (0..n).filter(|&v| tree[v].len() % 2 == 1).collect();
+
+    let mut edges: Vec<(usize, usize)> = (0..n).flat_map(|v| tree[v].iter().filter(move |&&w| v < w).map(move |&w| (v, w))).collect();
+    edges.extend(matching(cities, &odd));
+    let mut incident: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (e, &(a, b)) in edges.iter().enumerate() {
+        incident[a].push(e);
+        incident[b].push(e);
+    }
+
+    let mut used = vec![false; edges.len()];
+    let mut cursor = vec![0; n];
+    let mut stack = vec![0];
+    let mut circuit = Vec::with_capacity(edges.len() + 1);
+    while let Some(&v) = stack.last() {
+        while cursor[v] < incident[v].len() && used[incident[v][cursor[v]]] {
+            cursor[v] += 1;
+        }
+        match incident[v].get(cursor[v]) {
+            Some(&e) => {
+                used[e] = true;
+                let (a, b) = edges[e];
+                stack.push(if a == v { b } else { a });
+            }
+            None => circuit.push(stack.pop().unwrap()),
+        }
+    }
+
+    let mut seen = vec![false; n];
+    circuit.into_iter().filter(|&v| !std::mem::replace(&mut seen[v], true)).collect()
+}
+
+/// Build a GA population of `size` individuals. `round(fraction * size)` of them
+/// come from nearest-neighbour / random insertion (alternating), each perturbed
+/// with a few double-bridge kicks (none below 4 cities); the rest are random
+/// permutations.
+/// Every slot uses its own RNG stream derived from `seed`, so the result is
+/// reproducible and construction runs in parallel.
+pub fn seeded_population(cities: &[City], size: usize, fraction: f64, seed: u64) -> Vec<Individual> {
+    let n = cities.len();
+    let heuristic = ((fraction.clamp(0.0, 1.0) * size as f64).round() as usize).min(size);
+    let kicks = 1 + n / 100;
+
+    (0..size)
+        .into_par_iter()
+        .map(|slot| {
+            let mut rng = stream_rng(seed, u64::MAX, slot as u64);
+            let mut tour: Vec<usize> = (0..n).collect();
+            tour.shuffle(&mut rng);
+
+            if slot < heuristic && n > 0 {
+                tour = if slot % 2 == 0 {
+                    nearest_neighbor_tour(rng.gen_range(0..n), cities)
+                } else {
+                    insertion_tour(&tour, cities)
+                };
+                for _ in 0..kicks {
+                    double_bridge_with_rng(&mut tour, &mut rng);
+                }
+            }
+            Individual::new(tour, cities)
+        })
+        .collect()
+}
@@ -0,0 +1,103 @@
+/// Incremental tour length from move deltas
+///
+/// Local search knows what every move changes: a 2-opt or Or-opt delta costs
+/// a few distance evaluations, a `compute_total_distance` costs `n`.
+/// `CostTracker` keeps the tour length as a running sum of the applied deltas.
+/// The sum is Kahan-compensated, so millions of small deltas (simulated
+/// annealing) do not accumulate rounding error against the large total.
+///
+/// Every `resync_every` updates `checkpoint` recomputes the exact length and
+/// restarts the sum from it. The difference found there is the drift: rounding
+/// keeps it around `n * 1e-16` of the length, so anything beyond
+/// `MAX_RELATIVE_DRIFT` means a move delta does not match the move that was
+/// applied. Debug builds assert on it; `max_drift` reports it either way.
+use crate::all_versions::utils::*;
+
+/// Updates between two exact resynchronizations
+const RESYNC_EVERY: usize = 10_000;
+
+/// Largest drift, relative to the tour length, that rounding can explain
+const MAX_RELATIVE_DRIFT: f64 = 1e-7;
+
+#[derive(Clone, Debug)]
+pub struct CostTracker {
+    cost: f64,
+    /// Kahan compensation: the low-order bits lost by the last addition
+    compensation: f64,
+    updates: usize,
+    resync_every: usize,
+    max_drift: f64,
+}
+
+impl CostTracker {
+    /// Start from the exact length of `tour`
+    pub fn new(tour: &[usize], cities: &[City]) -> Self {
+        Self {
+            cost: compute_total_distance(tour, cities),
+            compensation: 0.0,
+            updates: 0,
+            resync_every: RESYNC_EVERY,
+            max_drift: 0.0,
+        }
+    }
+
+    /// Resynchronize every `every` updates instead (at least 1)
+    #[allow(dead_code)]
+    pub fn with_resync_interval(mut self, every: usize) -> Self {
+        self.resync_every = every.max(1);
+        self
+    }
+
+    /// Tracked tour length
+    pub fn cost(&self) -> f64 {
+        self.cost
+    }
+
+    /// Largest drift found at a resynchronization so far
+    #[allow(dead_code)]
+    pub fn max_drift(&self) -> f64 {
+        self.max_drift
+    }
+
+    /// Add the length change `delta` of an applied move (negative = shorter)
+    pub fn add(&mut self, delta: f64) {
+        let y = delta - self.compensation;
+        let t = self.cost + y;
+        self.compensation = (t - self.cost) - y;
+        self.cost = t;
+        self.updates += 1;
+    }
+
+    /// Record an applied move that saved `gain`
+    pub fn saved(&mut self, gain: f64) {
+        self.add(-gain);
+    }
+
+    /// Resynchronize with `tour` if `resync_every` updates have passed since
+    /// the last time
+    pub fn checkpoint(&mut self, tour: &[usize], cities: &[City]) {
+        if self.updates >= self.resync_every {
+            self.resync(tour, cities);
+        }
+    }
+
+    /// Replace the tracked length by the exact length of `tour`, check the
+    /// drift, and return the exact length
+    pub fn resync(&mut self, tour: &[usize], cities: &[City]) -> f64 {
+        let exact = compute_total_distance(tour, cities);
+        let drift = (self.cost - exact).abs();
+        self.max_drift = self.max_drift.max(drift);
+        debug_assert!(
+            drift <= MAX_RELATIVE_DRIFT * exact.max(1.0),
+            "tracked cost {} drifted {} from the exact {} after {} updates",
+            self.cost,
+            drift,
+            exact,
+            self.updates
+        );
+        self.cost = exact;
+        self.compensation = 0.0;
+        self.updates = 0;
+        exact
+    }
+}
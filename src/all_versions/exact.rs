@@ -0,0 +1,260 @@
+/// Exact solvers for small instances, used as ground truth for the heuristics
+///
+/// - `held_karp`: bitmask dynamic programming over (visited set, last city),
+///   O(2^n n^2) time and O(2^n n) memory. Subsets are processed in layers of
+///   equal size; every layer only reads the previous one, so its subsets are
+///   evaluated in parallel. Limited to `HELD_KARP_MAX` cities (~90 MB at 20).
+/// - `branch_and_bound`: depth-first search over tours starting at city 0,
+///   one rayon task per second city, sharing the incumbent length. A partial
+///   tour 0 -> ... -> last is pruned with a 1-tree bound on the remaining path:
+///   MST of the unvisited cities plus their cheapest links to `last` and to 0,
+///   on edge costs shifted by the root's Held–Karp node penalties
+///   (`lower_bound`). Stops after `max_nodes` search nodes, reporting whether
+///   the result is proven optimal.
+/// - `solve_exact`: Held–Karp up to `HELD_KARP_MAX` cities, branch-and-bound
+///   from an ILS tour above
+use rayon::prelude::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use crate::all_versions::iterated_local_search::*;
+use crate::all_versions::lower_bound::*;
+use crate::all_versions::utils::*;
+
+/// Largest instance `held_karp` accepts
+pub const HELD_KARP_MAX: usize = 20;
+/// Largest instance `branch_and_bound` accepts (visited sets are `u64` masks)
+pub const BRANCH_AND_BOUND_MAX: usize = 64;
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct ExactResult {
+    pub tour: Vec<usize>,
+    pub cost: f64,
+    /// False if branch-and-bound ran out of nodes before closing the search
+    pub optimal: bool,
+    /// Search nodes expanded (0 for Held–Karp)
+    pub nodes: usize,
+}
+
+/// Gap of `cost` above `reference` (an optimum or a lower bound), in %
+#[allow(dead_code)]
+pub fn optimality_gap(cost: f64, reference: f64) -> f64 {
+    // equal lengths summed in a different order may differ in the last bits
+    if reference <= 0.0 || (cost - reference).abs() <= 1e-9 * reference {
+        return 0.0;
+    }
+    (cost / reference - 1.0) * 100.0
+}
+
+fn distance_matrix(cities: &[City]) -> Vec<f64> {
+    let n = cities.len();
+    (0..n * n).into_par_iter().map(|k| euclidean_distance(&cities[k / n], &cities[k % n])).collect()
+}
+
+/// Optimal tour by Held–Karp dynamic programming (`n <= HELD_KARP_MAX`)
+#[allow(dead_code)]
+pub fn held_karp(cities: &[City]) -> (Vec<usize>, f64) {
+    let n = cities.len();
+    assert!(n <= HELD_KARP_MAX, "held_karp supports at most {} cities, got {}", HELD_KARP_MAX, n);
+    if n < 3 {
+        let tour: Vec<usize> = (0..n).collect();
+        let cost = compute_total_distance(&tour, cities);
+        return (tour, cost);
+    }
+    let dist = distance_matrix(cities);
+    let d = |a: usize, b: usize| dist[a * n + b];
+
+    // City 0 is the fixed start; bit j of a subset stands for city j + 1
+    let m = n - 1;
+    let mut layers: Vec<Vec<u32>> = vec![Vec::new(); m + 1];
+    let mut rank = vec![0u32; 1 << m];
+    for mask in 1u32..(1 << m) {
+        let layer = &mut layers[mask.count_ones() as usize];
+        rank[mask as usize] = layer.len() as u32;
+        layer.push(mask);
+    }
+
+    // cost[s][r * m + j]: shortest path from 0 through the r-th subset of size s
+    // ending at city j + 1; parent[s][r * m + j] is the city before it (bit index)
+    let mut cost: Vec<Vec<f64>> = vec![Vec::new(); m + 1];
+    let mut parent: Vec<Vec<u8>> = vec![Vec::new(); m + 1];
+    cost[1] = vec![f64::INFINITY; m * m];
+    parent[1] = vec![0; m * m];
+    for (r, &mask) in layers[1].iter().enumerate() {
+        let j = mask.trailing_zeros() as usize;
+        cost[1][r * m + j] = d(0, j + 1);
+    }
+
+    for s in 2..=m {
+        let prev_cost = &cost[s - 1];
+        let mut layer_cost = vec![f64::INFINITY; layers[s].len() * m];
+        let mut layer_parent = vec![0u8; layers[s].len() * m];
+        layer_cost
+            .par_chunks_mut(m)
+            .zip(layer_parent.par_chunks_mut(m))
+            .zip(layers[s].par_iter())
+            .for_each(|((row, row_parent), &mask)| {
+                for j in (0..m).filter(|&j| mask & (1 << j) != 0) {
+                    let prev = mask ^ (1 << j);
+                    let base = rank[prev as usize] as usize * m;
+                    for i in (0..m).filter(|&i| prev & (1 << i) != 0) {
+                        let c = prev_cost[base + i] + d(i + 1, j + 1);
+                        if c < row[j] {
+                            row[j] = c;
+                            row_parent[j] = i as u8;
+                        }
+                    }
+                }
+            });
+        cost[s] = layer_cost;
+        parent[s] = layer_parent;
+    }
+
+    // Close the cycle, then walk the parents back
+    let (mut j, best) = (0..m)
+        .map(|j| (j, cost[m][j] + d(j + 1, 0)))
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .unwrap();
+    let mut mask = (1u32 << m) - 1;
+    let mut path = Vec::with_capacity(n);
+    for s in (1..=m).rev() {
+        path.push(j + 1);
+        let i = parent[s][rank[mask as usize] as usize * m + j] as usize;
+        mask ^= 1 << j;
+        j = i;
+    }
+    path.push(0);
+    path.reverse();
+    (path, best)
+}
+
+/// Minimum spanning tree weight over `nodes` with edge cost `w` (dense Prim)
+fn mst_weight(nodes: &[usize], w: impl Fn(usize, usize) -> f64) -> f64 {
+    if nodes.len() < 2 {
+        return 0.0;
+    }
+    let mut in_tree = vec![false; nodes.len()];
+    let mut key: Vec<f64> = nodes.iter().map(|&v| w(nodes[0], v)).collect();
+    in_tree[0] = true;
+    let mut total = 0.0;
+    for _ in 1..nodes.len() {
+        let (next, _) = key
+            .iter()
+            .enumerate()
+            .filter(|&(k, _)| !in_tree[k])
+            .min_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .unwrap();
+        in_tree[next] = true;
+        total += key[next];
+        for k in 0..nodes.len() {
+            if !in_tree[k] {
+                key[k] = key[k].min(w(nodes[next], nodes[k]));
+            }
+        }
+    }
+    total
+}
+
+struct Search<'a> {
+    n: usize,
+    dist: &'a [f64],
+    pi: &'a [f64],
+    best_bits: &'a AtomicU64,
+    nodes: &'a AtomicUsize,
+    max_nodes: usize,
+    aborted: &'a AtomicBool,
+}
+
+impl Search<'_> {
+    fn d(&self, a: usize, b: usize) -> f64 {
+        self.dist[a * self.n + b]
+    }
+
+    fn best(&self) -> f64 {
+        f64::from_bits(self.best_bits.load(Ordering::Relaxed))
+    }
+
+    /// Lower bound on the path from `last` through all unvisited cities back to 0
+    fn remaining_bound(&self, last: usize, visited: u64) -> f64 {
+        let rest: Vec<usize> = (0..self.n).filter(|&v| visited & (1 << v) == 0).collect();
+        if rest.is_empty() {
+            return self.d(last, 0);
+        }
+        let pi = self.pi;
+        let w = |a: usize, b: usize| self.d(a, b) + pi[a] + pi[b];
+        let link = |end: usize| rest.iter().map(|&v| self.d(end, v) + pi[v]).fold(f64::INFINITY, f64::min);
+        mst_weight(&rest, w) + link(last) + link(0) - 2.0 * rest.iter().map(|&v| pi[v]).sum::<f64>()
+    }
+
+    /// Depth-first search below `path`; the best complete tour found is kept in `found`
+    fn dfs(&self, path: &mut Vec<usize>, visited: u64, length: f64, found: &mut Option<(Vec<usize>, f64)>) {
+        if self.nodes.fetch_add(1, Ordering::Relaxed) >= self.max_nodes {
+            self.aborted.store(true, Ordering::Relaxed);
+            return;
+        }
+        let last = *path.last().unwrap();
+        if path.len() == self.n {
+            let cost = length + self.d(last, 0);
+            if cost < self.best() - 1e-9 {
+                // non-negative f64s order like their bit patterns
+                self.best_bits.fetch_min(cost.to_bits(), Ordering::Relaxed);
+                *found = Some((path.clone(), cost));
+            }
+            return;
+        }
+        if length + self.remaining_bound(last, visited) >= self.best() - 1e-9 {
+            return;
+        }
+        let mut next: Vec<usize> = (0..self.n).filter(|&v| visited & (1 << v) == 0).collect();
+        next.sort_by(|&a, &b| self.d(last, a).partial_cmp(&self.d(last, b)).unwrap());
+        for v in next {
+            path.push(v);
+            self.dfs(path, visited | (1 << v), length + self.d(last, v), found);
+            path.pop();
+        }
+    }
+}
+
+/// Branch-and-bound from the upper bound `initial`, expanding at most `max_nodes` nodes
+#[allow(dead_code)]
+pub fn branch_and_bound(cities: &[City], initial: &[usize], max_nodes: usize) -> ExactResult {
+    let n = cities.len();
+    assert!(n <= BRANCH_AND_BOUND_MAX, "branch_and_bound supports at most {} cities, got {}", BRANCH_AND_BOUND_MAX, n);
+    let initial_cost = compute_total_distance(initial, cities);
+    if n < 4 {
+        return ExactResult { tour: initial.to_vec(), cost: initial_cost, optimal: true, nodes: 0 };
+    }
+    let dist = distance_matrix(cities);
+    let config = LowerBoundConfig { halve_after: 0, stop: StopCondition::iterations(100 * n), ..LowerBoundConfig::default() };
+    let pi = held_karp_bound_with(n, |a, b| dist[a * n + b], initial_cost, &config).penalties;
+
+    let best_bits = AtomicU64::new(initial_cost.to_bits());
+    let nodes = AtomicUsize::new(0);
+    let aborted = AtomicBool::new(false);
+    let search = Search { n, dist: &dist, pi: &pi, best_bits: &best_bits, nodes: &nodes, max_nodes, aborted: &aborted };
+
+    let best = (1..n)
+        .into_par_iter()
+        .filter_map(|second| {
+            let mut found = None;
+            let mut path = vec![0, second];
+            search.dfs(&mut path, 1 | (1 << second), search.d(0, second), &mut found);
+            found
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+    let (tour, cost) = best.unwrap_or_else(|| (initial.to_vec(), initial_cost));
+    ExactResult { tour, cost, optimal: !aborted.into_inner(), nodes: nodes.into_inner() }
+}
+
+/// Optimal tour: Held–Karp up to `HELD_KARP_MAX` cities, otherwise
+/// branch-and-bound (at most `max_nodes` nodes) seeded with an ILS tour
+#[allow(dead_code)]
+pub fn solve_exact(cities: &[City], max_nodes: usize) -> ExactResult {
+    if cities.len() <= HELD_KARP_MAX {
+        let (tour, cost) = held_karp(cities);
+        return ExactResult { tour, cost, optimal: true, nodes: 0 };
+    }
+    let start: Vec<usize> = (0..cities.len()).collect();
+    let (initial, _) = iterated_local_search(&start, cities, &IlsConfig::default());
+    branch_and_bound(cities, &initial, max_nodes)
+}
@@ -0,0 +1,209 @@
+/// Adaptive parameter control for the parallel GA
+///
+/// - `MutationControl` decides the mutation rate each generation: the original
+///   linear decay, Rechenberg's 1/5th success rule, or a rate driven by
+///   population diversity.
+/// - `CreditConfig` enables operator credit assignment: each child picks its
+///   crossover and mutation operator with probabilities matched to the recent
+///   reward of that operator (probability matching with a floor).
+///
+/// The controller state lives in `AdaptiveState`, which is part of the GA
+/// checkpoint so a resumed run makes the same decisions.
+use rand::Rng;
+use std::collections::HashSet;
+use crate::all_versions::utils::*;
+
+/// How the per-generation mutation rate is chosen
+#[allow(dead_code)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MutationControl {
+    /// `base * (1 - gen / generations)`
+    LinearDecay,
+    /// Raise the rate by `1 / factor` when more than 1/5 of mutated children beat
+    /// their better parent, lower it by `factor` otherwise
+    OneFifth { factor: f64, min_rate: f64, max_rate: f64 },
+    /// Interpolate from `max_rate` (converged population) to `min_rate` (fully diverse)
+    Diversity { min_rate: f64, max_rate: f64 },
+}
+
+/// Operator credit assignment settings
+#[derive(Clone, Debug)]
+pub struct CreditConfig {
+    /// Weight of the latest generation in each operator's quality estimate
+    pub learning_rate: f64,
+    /// No operator's selection probability drops below this
+    pub min_probability: f64,
+}
+
+impl Default for CreditConfig {
+    fn default() -> Self {
+        Self { learning_rate: 0.3, min_probability: 0.05 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MutationOp {
+    Swap,
+    Inversion,
+    Insertion,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CrossoverOp {
+    Order,
+    PartiallyMapped,
+    Cycle,
+}
+
+pub const MUTATION_OPS: [MutationOp; 3] = [MutationOp::Swap, MutationOp::Inversion, MutationOp::Insertion];
+pub const CROSSOVER_OPS: [CrossoverOp; 3] = [CrossoverOp::Order, CrossoverOp::PartiallyMapped, CrossoverOp::Cycle];
+
+impl MutationOp {
+    pub fn apply<R: Rng + ?Sized>(self, tour: &mut Vec<usize>, rng: &mut R) {
+        match self {
+            MutationOp::Swap => swap_mutation_with_rng(tour, rng),
+            MutationOp::Inversion => inversion_mutation_with_rng(tour, rng),
+            MutationOp::Insertion => insertion_mutation_with_rng(tour, rng),
+        }
+    }
+}
+
+impl CrossoverOp {
+    pub fn apply<R: Rng + ?Sized>(self, p1: &[usize], p2: &[usize], rng: &mut R) -> Vec<usize> {
+        match self {
+            CrossoverOp::Order => order_crossover_with_rng(p1, p2, rng),
+            CrossoverOp::PartiallyMapped => pmx_crossover_with_rng(p1, p2, rng),
+            CrossoverOp::Cycle => cycle_crossover(p1, p2),
+        }
+    }
+}
+
+/// What happened to one child, fed back into the controller
+#[derive(Clone, Copy, Debug)]
+pub struct ChildOutcome {
+    pub crossover: usize,
+    /// Index into `MUTATION_OPS` if the child was mutated
+    pub mutation: Option<usize>,
+    /// Relative improvement over the better parent, 0 if not better
+    pub reward: f64,
+}
+
+/// Controller state carried across generations (and checkpoints)
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdaptiveState {
+    /// Current rate for `OneFifth`; unused by the other controls
+    pub mutation_rate: f64,
+    pub mutation_quality: Vec<f64>,
+    pub crossover_quality: Vec<f64>,
+}
+
+/// Decisions made for one generation, recorded in the generation log
+#[derive(Clone, Debug, Default)]
+pub struct AdaptiveDecision {
+    pub success_rate: Option<f64>,
+    pub diversity: Option<f64>,
+    pub mutation_probs: Vec<f64>,
+    pub crossover_probs: Vec<f64>,
+}
+
+impl AdaptiveState {
+    pub fn new(base_mutation_rate: f64) -> Self {
+        Self {
+            mutation_rate: base_mutation_rate,
+            mutation_quality: vec![0.0; MUTATION_OPS.len()],
+            crossover_quality: vec![0.0; CROSSOVER_OPS.len()],
+        }
+    }
+
+    /// Probability matching: p_i = p_min + (1 - K p_min) * q_i / sum(q)
+    pub fn probabilities(quality: &[f64], credit: &CreditConfig) -> Vec<f64> {
+        let k = quality.len() as f64;
+        let p_min = credit.min_probability.min(1.0 / k);
+        let total: f64 = quality.iter().sum();
+        quality
+            .iter()
+            .map(|&q| {
+                let share = if total > 0.0 { q / total } else { 1.0 / k };
+                p_min + (1.0 - k * p_min) * share
+            })
+            .collect()
+    }
+
+    /// Roulette-wheel pick of an operator index
+    pub fn pick<R: Rng + ?Sized>(probs: &[f64], rng: &mut R) -> usize {
+        let mut r = rng.gen::<f64>();
+        for (i, &p) in probs.iter().enumerate() {
+            if r < p {
+                return i;
+            }
+            r -= p;
+        }
+        probs.len() - 1
+    }
+
+    /// Fold one generation's outcomes into the controller
+    pub fn update(
+        &mut self,
+        outcomes: &[ChildOutcome],
+        control: &MutationControl,
+        credit: Option<&CreditConfig>,
+        decision: &mut AdaptiveDecision,
+    ) {
+        let mutated: Vec<&ChildOutcome> = outcomes.iter().filter(|o| o.mutation.is_some()).collect();
+        if !mutated.is_empty() {
+            let successes = mutated.iter().filter(|o| o.reward > 0.0).count();
+            let success_rate = successes as f64 / mutated.len() as f64;
+            decision.success_rate = Some(success_rate);
+
+            if let MutationControl::OneFifth { factor, min_rate, max_rate } = *control {
+                self.mutation_rate = if success_rate > 0.2 {
+                    self.mutation_rate / factor
+                } else if success_rate < 0.2 {
+                    self.mutation_rate * factor
+                } else {
+                    self.mutation_rate
+                }
+                .clamp(min_rate, max_rate);
+            }
+        }
+
+        if let Some(credit) = credit {
+            let alpha = credit.learning_rate;
+            for (i, q) in self.crossover_quality.iter_mut().enumerate() {
+                if let Some(mean) = mean_reward(outcomes.iter().filter(|o| o.crossover == i)) {
+                    *q = (1.0 - alpha) * *q + alpha * mean;
+                }
+            }
+            for (i, q) in self.mutation_quality.iter_mut().enumerate() {
+                if let Some(mean) = mean_reward(mutated.iter().copied().filter(|o| o.mutation == Some(i))) {
+                    *q = (1.0 - alpha) * *q + alpha * mean;
+                }
+            }
+        }
+    }
+}
+
+fn mean_reward<'a>(outcomes: impl Iterator<Item = &'a ChildOutcome>) -> Option<f64> {
+    let (sum, count) = outcomes.fold((0.0, 0usize), |(s, c), o| (s + o.reward, c + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Share of distinct edges in the population, scaled to 0 (all tours share
+/// the same edges) ..= 1 (no edge appears in two tours)
+pub fn population_diversity(population: &[Individual]) -> f64 {
+    let Some(first) = population.first() else {
+        return 0.0;
+    };
+    let n = first.tour.len();
+    if population.len() < 2 || n < 3 {
+        return 0.0;
+    }
+    let mut edges: HashSet<(usize, usize)> = HashSet::new();
+    for ind in population {
+        for i in 0..n {
+            let (a, b) = (ind.tour[i], ind.tour[(i + 1) % n]);
+            edges.insert((a.min(b), a.max(b)));
+        }
+    }
+    (edges.len() - n) as f64 / (n * (population.len() - 1)) as f64
+}
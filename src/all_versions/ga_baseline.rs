@@ -1,9 +1,30 @@
 /// GA Version 1️⃣ with Elitism and Final 2-Opt
-use rand::seq::SliceRandom;
 use rand::Rng;
 use rand::thread_rng;
+use crate::all_versions::construction::seeded_population;
 use crate::all_versions::utils::*;
 
+/// Parameters of the baseline GA. Field meanings match `run_ga_baseline`'s arguments.
+#[derive(Clone, Debug)]
+pub struct GaBaselineParams {
+    pub population_size: usize,
+    pub generations: usize,
+    pub base_mutation_rate: f64,
+    /// Fraction of the initial population built by construction heuristics
+    pub seed_fraction: f64,
+}
+
+impl Default for GaBaselineParams {
+    fn default() -> Self {
+        Self {
+            population_size: 100,
+            generations: 300,
+            base_mutation_rate: 0.1,
+            seed_fraction: 0.0,
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn run_ga_baseline(
     cities: &[City],
@@ -11,7 +32,19 @@ pub fn run_ga_baseline(
     generations: usize,
     base_mutation_rate: f64,
 ) -> Individual {
-    let city_count = cities.len();
+    let params = GaBaselineParams {
+        population_size,
+        generations,
+        base_mutation_rate,
+        ..GaBaselineParams::default()
+    };
+    run_ga_baseline_with(cities, &params)
+}
+
+/// Baseline GA with heuristic seeding
+#[allow(dead_code)]
+pub fn run_ga_baseline_with(cities: &[City], params: &GaBaselineParams) -> Individual {
+    let GaBaselineParams { population_size, generations, base_mutation_rate, seed_fraction } = *params;
     let mut rng = thread_rng();
 
     let elitism_k = 5;
@@ -19,13 +52,7 @@ pub fn run_ga_baseline(
     let mut best_fitness_so_far = f64::MIN;
     let mut generations_without_improvement = 0;
 
-    let mut population: Vec<Individual> = (0..population_size)
-        .map(|_| {
-            let mut tour: Vec<usize> = (0..city_count).collect();
-            tour.shuffle(&mut rng);
-            Individual::new(tour, cities)
-        })
-        .collect();
+    let mut population = seeded_population(cities, population_size, seed_fraction, rng.gen());
 
     for gen in 0..generations {
         let mut new_population = Vec::with_capacity(population_size);
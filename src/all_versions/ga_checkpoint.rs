@@ -0,0 +1,189 @@
+/// GA checkpointing: save / restore the evolving state of `par_ga` to disk
+///
+/// A checkpoint holds everything the generation loop needs to continue:
+/// population tours, the next generation index, the best tour so far,
+/// the stagnation counter, the adaptive-control state and the RNG state.
+/// The GA draws every random number from `stream_rng(seed, generation, slot)`,
+/// so the RNG state is fully described by the seed plus the generation index.
+///
+/// File format (plain text, one record per line):
+/// ```text
+/// tsp-ga-checkpoint v2
+/// cities <n>
+/// seed <u64>
+/// generation <next generation to run>
+/// stagnation <generations without improvement>
+/// adaptive <rate> <mutation qualities...> <crossover qualities...>   (f64 bit patterns, hex)
+/// best <tour...> | best -
+/// population <count>
+/// <tour...>            (repeated `count` times)
+/// ```
+use std::fs;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use crate::all_versions::ga_adaptive::*;
+use crate::all_versions::utils::*;
+
+const HEADER: &str = "tsp-ga-checkpoint v2";
+
+/// Where and how often the GA writes checkpoints
+#[derive(Clone, Debug)]
+pub struct CheckpointConfig {
+    pub path: PathBuf,
+    /// Write a checkpoint after every `every` generations (0 disables)
+    pub every: usize,
+}
+
+/// Resumable state of the generation loop
+#[derive(Clone)]
+pub struct GaState {
+    pub seed: u64,
+    /// Index of the next generation to run
+    pub generation: usize,
+    pub population: Vec<Individual>,
+    pub best_so_far: Option<Individual>,
+    pub generations_without_improvement: usize,
+    pub adaptive: AdaptiveState,
+}
+
+impl GaState {
+    pub fn best_fitness_so_far(&self) -> f64 {
+        self.best_so_far.as_ref().map_or(f64::MIN, |b| b.fitness)
+    }
+}
+
+fn write_tour<W: Write>(out: &mut W, tour: &[usize]) -> io::Result<()> {
+    let line: Vec<String> = tour.iter().map(|c| c.to_string()).collect();
+    writeln!(out, "{}", line.join(" "))
+}
+
+/// Write `state` to `path`. The file is written next to the target and then
+/// renamed, so an interrupted write never clobbers the previous checkpoint.
+pub fn save_checkpoint(path: &Path, state: &GaState, city_count: usize) -> io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    {
+        let mut out = BufWriter::new(fs::File::create(&tmp)?);
+        writeln!(out, "{}", HEADER)?;
+        writeln!(out, "cities {}", city_count)?;
+        writeln!(out, "seed {}", state.seed)?;
+        writeln!(out, "generation {}", state.generation)?;
+        writeln!(out, "stagnation {}", state.generations_without_improvement)?;
+        let a = &state.adaptive;
+        let bits: Vec<String> = std::iter::once(&a.mutation_rate)
+            .chain(&a.mutation_quality)
+            .chain(&a.crossover_quality)
+            .map(|v| format!("{:016x}", v.to_bits()))
+            .collect();
+        writeln!(out, "adaptive {}", bits.join(" "))?;
+        match &state.best_so_far {
+            Some(best) => {
+                write!(out, "best ")?;
+                write_tour(&mut out, &best.tour)?;
+            }
+            None => writeln!(out, "best -")?,
+        }
+        writeln!(out, "population {}", state.population.len())?;
+        for ind in &state.population {
+            write_tour(&mut out, &ind.tour)?;
+        }
+        out.flush()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+fn invalid(msg: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn parse_tour(line: &str, city_count: usize) -> io::Result<Vec<usize>> {
+    let tour = line
+        .split_whitespace()
+        .map(|t| t.parse::<usize>().map_err(|e| invalid(format!("bad city index `{}`: {}", t, e))))
+        .collect::<io::Result<Vec<usize>>>()?;
+
+    let mut seen = vec![false; city_count];
+    if tour.len() != city_count
+        || tour.iter().any(|&c| c >= city_count || std::mem::replace(&mut seen[c], true))
+    {
+        return Err(invalid(format!("tour is not a permutation of {} cities", city_count)));
+    }
+    Ok(tour)
+}
+
+fn next_line<I: Iterator<Item = io::Result<String>>>(lines: &mut I, what: &str) -> io::Result<String> {
+    lines
+        .next()
+        .unwrap_or_else(|| Err(invalid(format!("checkpoint truncated before `{}`", what))))
+}
+
+fn field<I: Iterator<Item = io::Result<String>>>(lines: &mut I, key: &str) -> io::Result<String> {
+    let line = next_line(lines, key)?;
+    match line.split_once(' ') {
+        Some((k, v)) if k == key => Ok(v.to_string()),
+        _ => Err(invalid(format!("expected `{}`, found `{}`", key, line))),
+    }
+}
+
+fn number<I: Iterator<Item = io::Result<String>>>(lines: &mut I, key: &str) -> io::Result<u64> {
+    field(lines, key)?
+        .parse()
+        .map_err(|e| invalid(format!("bad `{}`: {}", key, e)))
+}
+
+/// Read a checkpoint written by `save_checkpoint` and rebuild the GA state.
+/// Fitness values are recomputed from the tours, so they match the original run bit-for-bit.
+pub fn load_checkpoint(path: &Path, cities: &[City]) -> io::Result<GaState> {
+    let mut lines = BufReader::new(fs::File::open(path)?).lines();
+    let n = cities.len();
+
+    if next_line(&mut lines, "header")? != HEADER {
+        return Err(invalid("not a GA checkpoint".to_string()));
+    }
+    let stored_n = number(&mut lines, "cities")? as usize;
+    if stored_n != n {
+        return Err(invalid(format!("checkpoint is for {} cities, got {}", stored_n, n)));
+    }
+    let seed = number(&mut lines, "seed")?;
+    let generation = number(&mut lines, "generation")? as usize;
+    let stagnation = number(&mut lines, "stagnation")? as usize;
+    let values = field(&mut lines, "adaptive")?
+        .split_whitespace()
+        .map(|h| {
+            u64::from_str_radix(h, 16)
+                .map(f64::from_bits)
+                .map_err(|e| invalid(format!("bad `adaptive` value `{}`: {}", h, e)))
+        })
+        .collect::<io::Result<Vec<f64>>>()?;
+    if values.len() != 1 + MUTATION_OPS.len() + CROSSOVER_OPS.len() {
+        return Err(invalid(format!("expected {} `adaptive` values, found {}",
+            1 + MUTATION_OPS.len() + CROSSOVER_OPS.len(), values.len())));
+    }
+    let adaptive = AdaptiveState {
+        mutation_rate: values[0],
+        mutation_quality: values[1..1 + MUTATION_OPS.len()].to_vec(),
+        crossover_quality: values[1 + MUTATION_OPS.len()..].to_vec(),
+    };
+    let best = field(&mut lines, "best")?;
+    let best_so_far = if best == "-" {
+        None
+    } else {
+        Some(Individual::new(parse_tour(&best, n)?, cities))
+    };
+    let count = number(&mut lines, "population")? as usize;
+
+    let population = (0..count)
+        .map(|_| {
+            let line = next_line(&mut lines, "population")?;
+            Ok(Individual::new(parse_tour(&line, n)?, cities))
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    Ok(GaState {
+        seed,
+        generation,
+        population,
+        best_so_far,
+        generations_without_improvement: stagnation,
+        adaptive,
+    })
+}
@@ -1,11 +1,51 @@
 /// GA Version 2️⃣ Configurable with Logging + Delayed 2-Opt
-use std::fs::File;
-use std::io::{BufWriter, Write};
-use rand::seq::SliceRandom;
+/// `refine_policy` picks which individuals get the delayed 2-opt; with `log_path`
+/// every generation is written to a CSV (see `ga_log`).
+use std::path::PathBuf;
 use rand::Rng;
 use rand::thread_rng;
+use crate::all_versions::ga_log::*;
+use crate::all_versions::construction::seeded_population;
 use crate::all_versions::utils::*;
 
+/// Parameters of the configurable GA. Field meanings match `run_ga_config`'s
+/// arguments; the log path has the same type as `par_ga::GaParams::log_path`.
+#[derive(Clone, Debug)]
+pub struct GaConfigParams {
+    pub population_size: usize,
+    pub generations: usize,
+    pub base_mutation_rate: f64,
+    pub elitism_k: usize,
+    pub refine_start: usize,
+    pub refine_every: usize,
+    pub top_n: usize,
+    /// Which `top_n` individuals the delayed 2-opt refines
+    pub refine_policy: RefinePolicy,
+    pub early_stop_patience: usize,
+    /// Fraction of the initial population built by construction heuristics
+    pub seed_fraction: f64,
+    /// Per-generation CSV log (see `ga_log`)
+    pub log_path: Option<PathBuf>,
+}
+
+impl Default for GaConfigParams {
+    fn default() -> Self {
+        Self {
+            population_size: 100,
+            generations: 300,
+            base_mutation_rate: 0.1,
+            elitism_k: 5,
+            refine_start: 100,
+            refine_every: 100,
+            top_n: 10,
+            refine_policy: RefinePolicy::BestN,
+            early_stop_patience: 100,
+            seed_fraction: 0.0,
+            log_path: None,
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn run_ga_config(
     cities: &[City],
@@ -17,20 +57,43 @@ pub fn run_ga_config(
     refine_every: usize,
     top_n: usize,
 ) -> Individual {
-    let city_count = cities.len();
+    let params = GaConfigParams {
+        population_size,
+        generations,
+        base_mutation_rate,
+        elitism_k,
+        refine_start,
+        refine_every,
+        top_n,
+        ..GaConfigParams::default()
+    };
+    run_ga_config_with(cities, &params)
+}
+
+/// Configurable GA with a refinement policy, heuristic seeding and an optional log
+#[allow(dead_code)]
+pub fn run_ga_config_with(cities: &[City], params: &GaConfigParams) -> Individual {
+    let GaConfigParams {
+        population_size,
+        generations,
+        base_mutation_rate,
+        elitism_k,
+        refine_start,
+        refine_every,
+        top_n,
+        refine_policy,
+        early_stop_patience,
+        seed_fraction,
+        ..
+    } = *params;
     let mut rng = thread_rng();
 
-    let early_stop_patience = 100;
     let mut best_fitness_so_far = f64::MIN;
     let mut generations_without_improvement = 0;
+    let mut log = GenerationLog::open(params.log_path.as_deref(), false)
+        .expect("could not create GA log file");
 
-    let mut population: Vec<Individual> = (0..population_size)
-        .map(|_| {
-            let mut tour: Vec<usize> = (0..city_count).collect();
-            tour.shuffle(&mut rng);
-            Individual::new(tour, cities)
-        })
-        .collect();
+    let mut population = seeded_population(cities, population_size, seed_fraction, rng.gen());
 
     for gen in 0..generations {
         let mutation_rate = base_mutation_rate * (1.0 - gen as f64 / generations as f64);
@@ -58,13 +121,19 @@ pub fn run_ga_config(
         population = new_population;
 
         // Apply 2-opt refinement after delay
+        let mut refined_idx = Vec::new();
         if gen >= refine_start && gen % refine_every == 0 {
-            for i in 0..top_n.min(population.len()) {
+            refined_idx = select_for_refinement(&population, top_n, refine_policy, &mut rng);
+            for &i in &refined_idx {
                 let refined = two_opt(&population[i].tour, cities);
                 population[i] = Individual::new(refined, cities);
             }
+            println!("Generation {}: refined {:?} ({:?})", gen, refined_idx, refine_policy);
         }
 
+        let stats = GenerationStats::new(gen, &population, mutation_rate, refined_idx);
+        log.record(&stats).expect("could not write GA log");
+
         // Status print every 50 generations or final
         let best = population
             .iter()
@@ -97,6 +166,8 @@ pub fn run_ga_config(
         }
     }
 
+    log.flush().expect("could not write GA log");
+
     let best = population
         .into_iter()
         .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
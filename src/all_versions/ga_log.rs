@@ -0,0 +1,102 @@
+/// Per-generation GA statistics and an optional CSV log of them
+///
+/// Columns: `generation,best,avg,median,mutation_rate,refined,success_rate,diversity,mutation_probs,crossover_probs`
+/// where the distances are tour lengths, `refined` lists the population indices
+/// that received 2-opt refinement this generation (space separated), and the last
+/// four record the adaptive controller's inputs and operator probabilities
+/// (empty when the corresponding control is off; see `ga_adaptive`).
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use crate::all_versions::ga_adaptive::AdaptiveDecision;
+use crate::all_versions::utils::*;
+
+/// Summary of one generation
+#[derive(Clone, Debug)]
+pub struct GenerationStats {
+    pub generation: usize,
+    pub best: f64,
+    pub avg: f64,
+    pub median: f64,
+    pub mutation_rate: f64,
+    /// Population indices refined this generation
+    pub refined: Vec<usize>,
+    pub adaptive: AdaptiveDecision,
+}
+
+impl GenerationStats {
+    pub fn new(generation: usize, population: &[Individual], mutation_rate: f64, refined: Vec<usize>) -> Self {
+        let mut distances: Vec<f64> = population.iter().map(|ind| ind.distance()).collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Self {
+            generation,
+            best: distances[0],
+            avg: distances.iter().sum::<f64>() / distances.len() as f64,
+            median: distances[distances.len() / 2],
+            mutation_rate,
+            refined,
+            adaptive: AdaptiveDecision::default(),
+        }
+    }
+}
+
+/// CSV sink for `GenerationStats`; a no-op when created without a path
+pub struct GenerationLog {
+    out: Option<BufWriter<File>>,
+}
+
+impl GenerationLog {
+    /// Open `path` for logging. With `append`, rows are added to an existing
+    /// log (used when resuming from a checkpoint) instead of starting over.
+    pub fn open(path: Option<&Path>, append: bool) -> io::Result<Self> {
+        let Some(path) = path else {
+            return Ok(Self { out: None });
+        };
+        let fresh = !append || !path.exists();
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(!fresh)
+            .truncate(fresh)
+            .open(path)?;
+        let mut out = BufWriter::new(file);
+        if fresh {
+            writeln!(
+                out,
+                "generation,best,avg,median,mutation_rate,refined,success_rate,diversity,mutation_probs,crossover_probs"
+            )?;
+        }
+        Ok(Self { out: Some(out) })
+    }
+
+    pub fn record(&mut self, stats: &GenerationStats) -> io::Result<()> {
+        let Some(out) = self.out.as_mut() else {
+            return Ok(());
+        };
+        let refined: Vec<String> = stats.refined.iter().map(|i| i.to_string()).collect();
+        let opt = |v: Option<f64>| v.map_or(String::new(), |v| format!("{:.4}", v));
+        let probs = |p: &[f64]| p.iter().map(|v| format!("{:.3}", v)).collect::<Vec<_>>().join(" ");
+        let a = &stats.adaptive;
+        writeln!(
+            out,
+            "{},{:.4},{:.4},{:.4},{:.5},{},{},{},{},{}",
+            stats.generation,
+            stats.best,
+            stats.avg,
+            stats.median,
+            stats.mutation_rate,
+            refined.join(" "),
+            opt(a.success_rate),
+            opt(a.diversity),
+            probs(&a.mutation_probs),
+            probs(&a.crossover_probs)
+        )
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        match self.out.as_mut() {
+            Some(out) => out.flush(),
+            None => Ok(()),
+        }
+    }
+}
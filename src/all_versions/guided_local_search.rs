@@ -0,0 +1,153 @@
+/// Guided local search (Voudouris & Tsang) with edge penalties
+///
+/// When the neighbour-list 2-opt / Or-opt descent reaches a local optimum, the
+/// tour edges with the highest utility `dist / (1 + penalty)` are penalised and
+/// the descent is rerun on the augmented cost `dist + lambda * penalty`, starting
+/// only from the endpoints of the newly penalised edges. Long edges that keep
+/// reappearing in local optima thus become expensive and the search is pushed
+/// elsewhere. The best tour is tracked under the true length.
+///
+/// - `guided_local_search`: one search
+/// - `parallel_gls`: one search per rayon thread, each from the start tour
+///   perturbed by a different number of double-bridge kicks (worker 0 starts
+///   unperturbed); the best result wins
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::candidate_set::Candidates;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::local_search::*;
+use crate::all_versions::utils::*;
+
+#[derive(Clone, Debug)]
+pub struct GlsConfig {
+    pub local_search: LocalSearchKind,
+    /// lambda = alpha * (length of the first local optimum / n)
+    pub alpha: f64,
+    /// Candidate lists of the local search
+    pub candidates: Candidates,
+    /// One iteration = one penalise-and-descend round
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for GlsConfig {
+    fn default() -> Self {
+        Self {
+            local_search: LocalSearchKind::Both,
+            alpha: 0.3,
+            candidates: Candidates::Nearest(8),
+            stop: StopCondition { max_iterations: 5000, time_limit: None, patience: 2000 },
+            seed: 121,
+        }
+    }
+}
+
+/// Penalty counts, stored per city as a short list of (other endpoint, count):
+/// only a small fraction of the edges is ever penalised.
+struct Penalties {
+    per_city: Vec<Vec<(usize, u32)>>,
+}
+
+impl Penalties {
+    fn get(&self, a: usize, b: usize) -> u32 {
+        self.per_city[a].iter().find(|&&(c, _)| c == b).map_or(0, |&(_, p)| p)
+    }
+
+    fn bump(&mut self, a: usize, b: usize) {
+        for (x, y) in [(a, b), (b, a)] {
+            match self.per_city[x].iter_mut().find(|(c, _)| *c == y) {
+                Some((_, p)) => *p += 1,
+                None => self.per_city[x].push((y, 1)),
+            }
+        }
+    }
+}
+
+/// Guided local search starting from `tour`
+#[allow(dead_code)]
+pub fn guided_local_search(tour: &[usize], cities: &[City], config: &GlsConfig) -> (Vec<usize>, f64) {
+    let neighbors = config.candidates.build(cities);
+    search(tour, cities, &neighbors, config, true)
+}
+
+fn search(tour: &[usize], cities: &[City], neighbors: &[Vec<usize>], config: &GlsConfig, verbose: bool) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    let mut t = ArrayTour::new(tour.to_vec());
+    improve(&mut t, cities, neighbors, config.local_search, 0, None);
+    let mut cost = CostTracker::new(&t.order, cities);
+    let mut best = t.order.clone();
+    let mut best_cost = cost.cost();
+    if n < 8 {
+        return (best, best_cost);
+    }
+
+    let start = Instant::now();
+    let lambda = config.alpha * best_cost / n as f64;
+    let mut penalties = Penalties { per_city: vec![Vec::new(); n] };
+
+    let mut iteration = 0;
+    let mut since_improvement = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) {
+        // Penalise the maximum-utility edges of the current local optimum
+        let utility = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]) / (1.0 + penalties.get(a, b) as f64);
+        let edges: Vec<(usize, usize)> = (0..n).map(|i| (t.order[i], t.order[(i + 1) % n])).collect();
+        let max_utility = edges.iter().map(|&(a, b)| utility(a, b)).fold(0.0, f64::max);
+        let chosen: Vec<(usize, usize)> = edges
+            .into_iter()
+            .filter(|&(a, b)| utility(a, b) >= max_utility - 1e-12)
+            .collect();
+        let mut active = Vec::with_capacity(2 * chosen.len());
+        for &(a, b) in &chosen {
+            penalties.bump(a, b);
+            active.extend([a, b]);
+        }
+
+        let penalty = |a: usize, b: usize| lambda * penalties.get(a, b) as f64;
+        let (_, delta) = improve_penalized(&mut t, cities, neighbors, config.local_search, Some(&active), &penalty);
+        cost.add(delta);
+        cost.checkpoint(&t.order, cities);
+        if cost.cost() < best_cost - 1e-9 {
+            best_cost = cost.cost();
+            best.copy_from_slice(&t.order);
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+
+        if verbose && iteration % 1000 == 0 {
+            println!("Iteration {}: current = {:.2} | best = {:.2}", iteration, cost.cost(), best_cost);
+        }
+        iteration += 1;
+    }
+
+    // A true-cost descent can still shorten the best tour slightly
+    let mut t = ArrayTour::new(best);
+    let (_, delta) = improve(&mut t, cities, neighbors, config.local_search, 0, None);
+    (t.order, best_cost + delta)
+}
+
+/// Parallel guided local search: independent searches from differently kicked starts
+#[allow(dead_code)]
+pub fn parallel_gls(tour: &[usize], cities: &[City], config: &GlsConfig) -> (Vec<usize>, f64) {
+    let neighbors = config.candidates.build(cities);
+    let workers = rayon::current_num_threads().max(1);
+
+    let results: Vec<(Vec<usize>, f64)> = (0..workers)
+        .into_par_iter()
+        .map(|w| {
+            let mut rng = stream_rng(config.seed, 0, w as u64);
+            let mut start = tour.to_vec();
+            for _ in 0..w {
+                double_bridge_with_rng(&mut start, &mut rng);
+            }
+            search(&start, cities, &neighbors, config, false)
+        })
+        .collect();
+
+    let (best, best_cost) = results
+        .into_iter()
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .unwrap();
+    println!("Best of {} GLS workers: {:.2}", workers, best_cost);
+    (best, best_cost)
+}
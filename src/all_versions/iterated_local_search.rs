@@ -0,0 +1,234 @@
+/// Iterated local search: descent -> double-bridge kick -> descent -> accept?
+///
+/// The tour lives in an `ArrayTour`; after a kick only the six cities at the
+/// kick's cut points are re-activated, so each iteration costs roughly the size
+/// of the disturbed region rather than a full O(n²) 2-opt pass. For large n the
+/// kick is segment-local: all three cuts fall inside a window of `kick_window`
+/// consecutive positions. A rejected kick is rolled back by undoing the
+/// recorded changes, not by copying the tour.
+///
+/// - `iterated_local_search`: one chain
+/// - `parallel_ils`: one chain per rayon thread; every `sync_interval` kicks all
+///   chains that are worse than the global best adopt it
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::candidate_set::Candidates;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::local_search::*;
+use crate::all_versions::utils::*;
+
+/// When a kicked-and-descended tour replaces the current one
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum Acceptance {
+    /// Strictly shorter than the current tour
+    Better,
+    /// Shorter than `(1 + fraction) * best`, allowing small sideways / uphill steps
+    Threshold(f64),
+}
+
+#[derive(Clone, Debug)]
+pub struct IlsConfig {
+    pub local_search: LocalSearchKind,
+    pub acceptance: Acceptance,
+    /// Positions spanned by a kick; `None` = whole tour up to 1000 cities, 100 above
+    pub kick_window: Option<usize>,
+    /// Candidate lists of the local search
+    pub candidates: Candidates,
+    /// Kicks per chain between global-best synchronisations (parallel mode)
+    pub sync_interval: usize,
+    /// One iteration = one kick (per chain)
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for IlsConfig {
+    fn default() -> Self {
+        Self {
+            local_search: LocalSearchKind::Both,
+            acceptance: Acceptance::Better,
+            kick_window: None,
+            candidates: Candidates::Nearest(8),
+            sync_interval: 200,
+            stop: StopCondition { max_iterations: 5000, time_limit: None, patience: 2000 },
+            seed: 121,
+        }
+    }
+}
+
+/// Double bridge with all cuts inside `window` consecutive positions starting at
+/// a random position: x0 [B] [C] x.. -> x0 [C] [B] x... Only the window is
+/// rewritten (`ArrayTour::rewrite`, so `rollback` can undo it). Returns the
+/// length change and the six cities at the cuts.
+pub fn double_bridge_local(t: &mut ArrayTour, window: usize, cities: &[City], rng: &mut StdRng) -> (f64, [usize; 6]) {
+    let n = t.len();
+    let window = window.clamp(8, n);
+    let start = rng.gen_range(0..n);
+    // positions start + 0 ..= start + window - 1; cuts strictly inside
+    let x: Vec<usize> = (0..window).map(|k| t.order[(start + k) % n]).collect();
+    let mut cuts = rand::seq::index::sample(rng, window - 2, 3).into_vec();
+    cuts.sort_unstable();
+    let (o1, o2, o3) = (cuts[0] + 1, cuts[1] + 1, cuts[2] + 1);
+
+    let dist = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]);
+    let delta = dist(x[o1 - 1], x[o2]) + dist(x[o3 - 1], x[o1]) + dist(x[o2 - 1], x[o3])
+        - dist(x[o1 - 1], x[o1]) - dist(x[o2 - 1], x[o2]) - dist(x[o3 - 1], x[o3]);
+
+    let rearranged: Vec<usize> = x[o2..o3].iter().chain(&x[o1..o2]).copied().collect();
+    t.rewrite(start + o1, &rearranged);
+    (delta, [x[o1 - 1], x[o1], x[o2 - 1], x[o2], x[o3 - 1], x[o3]])
+}
+
+/// One ILS chain: current tour, its length, and the chain's own best
+#[derive(Clone)]
+struct Chain {
+    t: ArrayTour,
+    cost: CostTracker,
+    best: Vec<usize>,
+    best_cost: f64,
+}
+
+impl Chain {
+    fn new(tour: &[usize], cities: &[City], neighbors: &[Vec<usize>], config: &IlsConfig) -> Self {
+        let mut t = ArrayTour::new(tour.to_vec());
+        improve(&mut t, cities, neighbors, config.local_search, 0, None);
+        let cost = CostTracker::new(&t.order, cities);
+        Self { best: t.order.clone(), best_cost: cost.cost(), t, cost }
+    }
+
+    fn cost(&self) -> f64 {
+        self.cost.cost()
+    }
+
+    /// Kick, descend, accept or roll back. Returns true if the chain's best improved.
+    fn step(&mut self, cities: &[City], neighbors: &[Vec<usize>], config: &IlsConfig, window: usize, rng: &mut StdRng) -> bool {
+        // A whole-tour kick is followed by long reversals, and undoing them costs
+        // more than copying the tour back; a windowed kick is undone in place
+        let snapshot = (2 * window > self.t.len()).then(|| self.t.order.clone());
+        if snapshot.is_none() {
+            self.t.begin();
+        }
+        let (kick, touched) = double_bridge_local(&mut self.t, window, cities, rng);
+        let (_, descent) = improve(&mut self.t, cities, neighbors, config.local_search, 0, Some(&touched));
+        let candidate = self.cost() + kick + descent;
+
+        let accept = match config.acceptance {
+            Acceptance::Better => candidate < self.cost() - 1e-9,
+            Acceptance::Threshold(fraction) => candidate < self.best_cost * (1.0 + fraction),
+        };
+        if !accept {
+            match snapshot {
+                Some(order) => self.t = ArrayTour::new(order),
+                None => self.t.rollback(),
+            }
+            return false;
+        }
+        self.t.commit();
+        self.cost.add(kick + descent);
+        self.cost.checkpoint(&self.t.order, cities);
+        if self.cost() < self.best_cost - 1e-9 {
+            self.best_cost = self.cost();
+            self.best.copy_from_slice(&self.t.order);
+            return true;
+        }
+        false
+    }
+
+    fn adopt(&mut self, tour: &[usize], cities: &[City]) {
+        self.t = ArrayTour::new(tour.to_vec());
+        self.cost = CostTracker::new(tour, cities);
+        self.best.copy_from_slice(tour);
+        self.best_cost = self.cost();
+    }
+}
+
+fn kick_window(config: &IlsConfig, n: usize) -> usize {
+    config.kick_window.unwrap_or(if n <= 1000 { n } else { 100 })
+}
+
+fn finish(best: Vec<usize>, cities: &[City]) -> (Vec<usize>, f64) {
+    let cost = compute_total_distance(&best, cities);
+    (best, cost)
+}
+
+/// Sequential ILS starting from `tour`
+#[allow(dead_code)]
+pub fn iterated_local_search(tour: &[usize], cities: &[City], config: &IlsConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 8 {
+        return finish(tour.to_vec(), cities);
+    }
+    let start = Instant::now();
+    let neighbors = config.candidates.build(cities);
+    let window = kick_window(config, n);
+    let mut rng = stream_rng(config.seed, 0, 0);
+    let mut chain = Chain::new(tour, cities, &neighbors, config);
+
+    let mut kick = 0;
+    let mut since_improvement = 0;
+    while !config.stop.should_stop(kick, since_improvement, start) {
+        if chain.step(cities, &neighbors, config, window, &mut rng) {
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+        if kick % 1000 == 0 {
+            println!("Kick {}: current = {:.2} | best = {:.2}", kick, chain.cost(), chain.best_cost);
+        }
+        kick += 1;
+    }
+    finish(chain.best, cities)
+}
+
+/// Parallel ILS: independent chains (one per rayon thread) that adopt the
+/// global best every `sync_interval` kicks
+#[allow(dead_code)]
+pub fn parallel_ils(tour: &[usize], cities: &[City], config: &IlsConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 8 {
+        return finish(tour.to_vec(), cities);
+    }
+    let start = Instant::now();
+    let neighbors = config.candidates.build(cities);
+    let window = kick_window(config, n);
+    let sync = config.sync_interval.max(1);
+    let threads = rayon::current_num_threads().max(1);
+
+    let first = Chain::new(tour, cities, &neighbors, config);
+    let mut chains: Vec<Chain> = (0..threads)
+        .map(|_| first.clone())
+        .collect();
+    let (mut best, mut best_cost) = (first.best, first.best_cost);
+
+    let mut round = 0;
+    let mut since_improvement = 0;
+    while !config.stop.should_stop(round * sync, since_improvement * sync, start) {
+        chains.par_iter_mut().enumerate().for_each(|(c, chain)| {
+            let mut rng = stream_rng(config.seed, round as u64, c as u64);
+            for _ in 0..sync {
+                chain.step(cities, &neighbors, config, window, &mut rng);
+            }
+        });
+
+        let leader = chains
+            .iter()
+            .min_by(|a, b| a.best_cost.partial_cmp(&b.best_cost).unwrap())
+            .unwrap();
+        if leader.best_cost < best_cost - 1e-9 {
+            best_cost = leader.best_cost;
+            best.copy_from_slice(&leader.best);
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+        for chain in chains.iter_mut().filter(|c| c.cost() > best_cost + 1e-9) {
+            chain.adopt(&best, cities);
+        }
+
+        println!("Round {}: best = {:.2} ({} chains)", round, best_cost, threads);
+        round += 1;
+    }
+    finish(best, cities)
+}
@@ -0,0 +1,266 @@
+/// Large neighbourhood search (ruin and recreate)
+///
+/// Each iteration removes a handful of cities from the current tour and
+/// re-inserts them. Every configured ruin operator is tried in parallel on the
+/// same current tour (one RNG stream per operator); the shortest of the
+/// recreated tours is then accepted or rejected against the current one.
+///
+/// - Ruin: `Random` cities, `Radial` (the cities closest to a random centre,
+///   from a k-d tree built once per run), or
+///   `String` (short runs of consecutive tour cities around a random centre)
+/// - Recreate: `Cheapest` insertion in random order, or `Regret(k)` insertion,
+///   which first places the city that would lose the most if its best position
+///   were taken (sum of the gaps to its 2nd..k-th best positions)
+/// - Acceptance: simulated annealing or record-to-record travel
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::construction::cheapest_insertion;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::local_search::position_index;
+use crate::all_versions::spatial_index::KdTree;
+use crate::all_versions::utils::*;
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ruin {
+    Random,
+    Radial,
+    String,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recreate {
+    Cheapest,
+    /// Regret-k insertion (k >= 2)
+    Regret(usize),
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug)]
+pub enum LnsAcceptance {
+    /// Metropolis criterion; the temperature, in units of the start tour's mean
+    /// edge length, decays geometrically from `start` to `end` over `max_iterations`
+    Annealing { start: f64, end: f64 },
+    /// Accept any tour shorter than `(1 + deviation) * best`
+    RecordToRecord { deviation: f64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct LnsConfig {
+    /// Ruin operators evaluated in parallel every iteration
+    pub ruins: Vec<Ruin>,
+    pub recreate: Recreate,
+    pub acceptance: LnsAcceptance,
+    /// Upper bound on cities removed per ruin (at least 2 are removed)
+    pub max_removed: usize,
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for LnsConfig {
+    fn default() -> Self {
+        Self {
+            ruins: vec![Ruin::Random, Ruin::Radial, Ruin::String],
+            recreate: Recreate::Cheapest,
+            acceptance: LnsAcceptance::Annealing { start: 0.1, end: 0.001 },
+            max_removed: 30,
+            stop: StopCondition { max_iterations: 3000, time_limit: None, patience: 1500 },
+            seed: 121,
+        }
+    }
+}
+
+/// Pick `count` cities to remove from `tour`
+fn ruin(kind: Ruin, tour: &[usize], pos: &[usize], count: usize, index: &KdTree, cities: &[City], rng: &mut StdRng) -> Vec<usize> {
+    let n = tour.len();
+    match kind {
+        Ruin::Random => rand::seq::index::sample(rng, n, count).into_iter().map(|p| tour[p]).collect(),
+        Ruin::Radial => index.k_nearest(&cities[rng.gen_range(0..n)], count),
+        Ruin::String => {
+            // strings of up to `max_len` consecutive cities, each containing one
+            // of the cities nearest the centre that is not removed yet; every
+            // city visited either starts a string or was removed by one, so
+            // the `2 * count` nearest suffice
+            let max_len = (count / 2).max(1);
+            let mut removed = vec![false; n];
+            let mut out = Vec::with_capacity(count);
+            for c in index.k_nearest(&cities[rng.gen_range(0..n)], 2 * count) {
+                if out.len() >= count {
+                    break;
+                }
+                if removed[c] {
+                    continue;
+                }
+                let len = rng.gen_range(1..=max_len.min(count - out.len()));
+                let first = pos[c] + n - rng.gen_range(0..len);
+                for k in 0..len {
+                    let city = tour[(first + k) % n];
+                    if !removed[city] {
+                        removed[city] = true;
+                        out.push(city);
+                    }
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Added length of inserting `city` at each position of `route`, cheapest first
+fn insertion_costs(route: &[usize], city: usize, cities: &[City], k: usize) -> Vec<(f64, usize)> {
+    let mut costs: Vec<(f64, usize)> = (0..route.len())
+        .map(|i| {
+            let (prev, next) = (route[i], route[(i + 1) % route.len()]);
+            let cost = euclidean_distance(&cities[prev], &cities[city])
+                + euclidean_distance(&cities[city], &cities[next])
+                - euclidean_distance(&cities[prev], &cities[next]);
+            (cost, i + 1)
+        })
+        .collect();
+    let k = k.min(costs.len());
+    costs.select_nth_unstable_by(k - 1, |a, b| a.0.partial_cmp(&b.0).unwrap());
+    costs.truncate(k);
+    costs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+    costs
+}
+
+/// Length change of dropping the cities marked `out` from `tour`: every run of
+/// removed cities is replaced by one edge between the kept cities around it
+fn removal_delta(tour: &[usize], pos: &[usize], removed: &[usize], out: &[bool], cities: &[City]) -> f64 {
+    let n = tour.len();
+    let dist = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]);
+    let mut delta = 0.0;
+    for &c in removed {
+        let prev = tour[(pos[c] + n - 1) % n];
+        if out[prev] {
+            continue; // not the first city of its run
+        }
+        let (mut last, mut p) = (prev, pos[c]);
+        while out[tour[p]] {
+            delta -= dist(last, tour[p]);
+            last = tour[p];
+            p = (p + 1) % n;
+        }
+        delta += dist(prev, tour[p]) - dist(last, tour[p]);
+    }
+    delta
+}
+
+/// Re-insert `removed` into the partial tour `route`; returns the added length
+fn recreate(kind: Recreate, route: &mut Vec<usize>, mut removed: Vec<usize>, cities: &[City], rng: &mut StdRng) -> f64 {
+    let mut added = 0.0;
+    match kind {
+        Recreate::Cheapest => {
+            removed.shuffle(rng);
+            for city in removed {
+                let (p, cost) = cheapest_insertion(route, city, cities);
+                route.insert(p, city);
+                added += cost;
+            }
+        }
+        Recreate::Regret(k) => {
+            let k = k.max(2);
+            while !removed.is_empty() {
+                let mut pick = (f64::NEG_INFINITY, 0, 0, 0.0);
+                for (r, &city) in removed.iter().enumerate() {
+                    let costs = insertion_costs(route, city, cities, k);
+                    let regret: f64 = costs.iter().skip(1).map(|c| c.0 - costs[0].0).sum();
+                    if regret > pick.0 {
+                        pick = (regret, r, costs[0].1, costs[0].0);
+                    }
+                }
+                let city = removed.swap_remove(pick.1);
+                route.insert(pick.2, city);
+                added += pick.3;
+            }
+        }
+    }
+    added
+}
+
+/// Ruin and recreate `tour` once; returns the new tour and its length change
+fn ruin_and_recreate(kind: Ruin, tour: &[usize], pos: &[usize], index: &KdTree, cities: &[City], config: &LnsConfig, rng: &mut StdRng) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    let count = rng.gen_range(2..=config.max_removed.clamp(2, n - 3));
+    let removed = ruin(kind, tour, pos, count, index, cities, rng);
+    let mut out = vec![false; n];
+    for &c in &removed {
+        out[c] = true;
+    }
+    let mut route: Vec<usize> = tour.iter().copied().filter(|&c| !out[c]).collect();
+    let removal = removal_delta(tour, pos, &removed, &out, cities);
+    let insertion = recreate(config.recreate, &mut route, removed, cities, rng);
+    (route, removal + insertion)
+}
+
+/// LNS starting from `tour`
+#[allow(dead_code)]
+pub fn large_neighborhood_search(tour: &[usize], cities: &[City], config: &LnsConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 8 || config.ruins.is_empty() {
+        let cost = compute_total_distance(tour, cities);
+        return (tour.to_vec(), cost);
+    }
+    let start = Instant::now();
+    let mut rng = stream_rng(config.seed, u64::MAX, 0);
+    let index = KdTree::new(cities);
+
+    let mut current = tour.to_vec();
+    let mut cost = CostTracker::new(&current, cities);
+    let mut best = current.clone();
+    let mut best_cost = cost.cost();
+    let mean_edge = best_cost / n as f64;
+    let horizon = config.stop.max_iterations.max(1) as f64;
+
+    let mut iteration = 0;
+    let mut since_improvement = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) {
+        let pos = position_index(&current);
+        let (candidate, delta) = config
+            .ruins
+            .par_iter()
+            .enumerate()
+            .map(|(r, &kind)| {
+                let mut rng = stream_rng(config.seed, iteration as u64, r as u64);
+                ruin_and_recreate(kind, &current, &pos, &index, cities, config, &mut rng)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+            .unwrap();
+        let candidate_cost = cost.cost() + delta;
+
+        let accept = match config.acceptance {
+            LnsAcceptance::Annealing { start, end } => {
+                let progress = (iteration as f64 / horizon).min(1.0);
+                let temperature = mean_edge * start * (end / start).powf(progress);
+                delta < 0.0 || rng.gen::<f64>() < (-delta / temperature).exp()
+            }
+            LnsAcceptance::RecordToRecord { deviation } => candidate_cost < best_cost * (1.0 + deviation),
+        };
+        if accept {
+            current = candidate;
+            cost.add(delta);
+            cost.checkpoint(&current, cities);
+        }
+
+        if cost.cost() < best_cost - 1e-9 {
+            best_cost = cost.cost();
+            best.copy_from_slice(&current);
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+
+        if iteration % 500 == 0 {
+            println!("Iteration {}: current = {:.2} | best = {:.2}", iteration, cost.cost(), best_cost);
+        }
+        iteration += 1;
+    }
+
+    (best, best_cost)
+}
@@ -0,0 +1,469 @@
+/// Fast bounded local search: 2-opt and Or-opt over k-nearest neighbour lists
+/// with don't-look bits.
+///
+/// Unlike `utils::two_opt` (full O(n²) scan until no improvement), these only
+/// look at moves that create an edge to one of a city's candidates (its `k`
+/// nearest neighbours, or any `candidate_set::CandidateSet`), and only revisit cities whose tour neighbourhood changed. A `budget` caps the
+/// number of city activations so callers (e.g. the memetic GA) can bound the
+/// time spent per tour.
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use crate::all_versions::spatial_index::KdTree;
+use crate::all_versions::utils::*;
+
+const EPS: f64 = 1e-9;
+
+/// Which local search to run
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LocalSearchKind {
+    TwoOpt,
+    OrOpt,
+    /// 2-opt and Or-opt moves tried from every activated city
+    Both,
+}
+
+/// `k` nearest neighbours of every city, closest first (k-d tree queries,
+/// parallel over cities)
+pub fn neighbor_lists(cities: &[City], k: usize) -> Vec<Vec<usize>> {
+    let k = k.min(cities.len().saturating_sub(1));
+    let tree = KdTree::new(cities);
+    (0..cities.len())
+        .into_par_iter()
+        .map(|a| {
+            let mut nbs = tree.k_nearest(&cities[a], k + 1);
+            nbs.retain(|&b| b != a);
+            nbs.truncate(k);
+            nbs
+        })
+        .collect()
+}
+
+/// `pos[city]` = index of `city` in `tour`
+pub fn position_index(tour: &[usize]) -> Vec<usize> {
+    let mut pos = vec![0; tour.len()];
+    for (i, &c) in tour.iter().enumerate() {
+        pos[c] = i;
+    }
+    pos
+}
+
+/// Reverse the `len` cities starting at position `from`, wrapping around the end
+/// of the array, and keep `pos` in sync.
+pub fn reverse_cyclic(tour: &mut [usize], pos: &mut [usize], from: usize, len: usize) {
+    let n = tour.len();
+    let (mut i, mut j) = (from % n, (from + len + n - 1) % n);
+    for _ in 0..len / 2 {
+        tour.swap(i, j);
+        pos[tour[i]] = i;
+        pos[tour[j]] = j;
+        i = (i + 1) % n;
+        j = (j + n - 1) % n;
+    }
+}
+
+/// Array tour with a city -> position index: O(1) succ / pred, moves applied
+/// as cyclic reversals. Shared by the local searches and the metaheuristics.
+/// Between `begin` and `commit` / `rollback` the changes are recorded, so a
+/// rejected change is undone in time proportional to what it touched.
+#[derive(Clone, Debug)]
+pub struct ArrayTour {
+    pub order: Vec<usize>,
+    pub pos: Vec<usize>,
+    /// Changes applied since `begin`, while recording
+    journal: Option<Vec<Change>>,
+}
+
+/// A recorded `ArrayTour` change
+#[derive(Clone, Debug)]
+enum Change {
+    /// `reverse_positions(start, len)`, its own inverse
+    Reverse(usize, usize),
+    /// `rewrite(start, ..)`, with the cities it overwrote
+    Rewrite(usize, Vec<usize>),
+}
+
+impl ArrayTour {
+    pub fn new(order: Vec<usize>) -> Self {
+        let pos = position_index(&order);
+        Self { order, pos, journal: None }
+    }
+
+    /// Reverse the `len` positions starting at position `start` (cyclically)
+    pub fn reverse_positions(&mut self, start: usize, len: usize) {
+        reverse_cyclic(&mut self.order, &mut self.pos, start, len);
+        if let Some(journal) = &mut self.journal {
+            journal.push(Change::Reverse(start, len));
+        }
+    }
+
+    /// Write `cities` to the positions starting at `start` (cyclically). They
+    /// must be a rearrangement of the cities currently there.
+    pub fn rewrite(&mut self, start: usize, cities: &[usize]) {
+        if let Some(journal) = &mut self.journal {
+            let n = self.order.len();
+            let old = (0..cities.len()).map(|k| self.order[(start + k) % n]).collect();
+            journal.push(Change::Rewrite(start, old));
+        }
+        self.write(start, cities);
+    }
+
+    fn write(&mut self, start: usize, cities: &[usize]) {
+        let n = self.order.len();
+        for (k, &c) in cities.iter().enumerate() {
+            let p = (start + k) % n;
+            self.order[p] = c;
+            self.pos[c] = p;
+        }
+    }
+
+    /// Start recording changes for `rollback`
+    pub fn begin(&mut self) {
+        self.journal = Some(Vec::new());
+    }
+
+    /// Keep the changes since `begin` and stop recording
+    pub fn commit(&mut self) {
+        self.journal = None;
+    }
+
+    /// Undo the changes since `begin`, newest first, and stop recording
+    pub fn rollback(&mut self) {
+        if let Some(journal) = self.journal.take() {
+            for change in journal.iter().rev() {
+                match change {
+                    Change::Reverse(start, len) => reverse_cyclic(&mut self.order, &mut self.pos, *start, *len),
+                    Change::Rewrite(start, old) => self.write(*start, old),
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    pub fn succ(&self, c: usize) -> usize {
+        self.order[(self.pos[c] + 1) % self.order.len()]
+    }
+
+    pub fn pred(&self, c: usize) -> usize {
+        let n = self.order.len();
+        self.order[(self.pos[c] + n - 1) % n]
+    }
+
+    /// Number of positions walked forward from `a` to reach `b`
+    pub fn span(&self, a: usize, b: usize) -> usize {
+        let n = self.order.len();
+        (self.pos[b] + n - self.pos[a]) % n
+    }
+
+    /// Reverse the tour path from city `from` forward to city `to` (inclusive).
+    /// On a cycle, reversing the rest of the tour instead gives the same
+    /// cycle, so the shorter of the two is reversed: at most n / 2 swaps. The
+    /// tour may then run the other way round, so callers must re-read
+    /// `succ` / `pred` afterwards.
+    pub fn reverse_path(&mut self, from: usize, to: usize) {
+        let n = self.order.len();
+        let len = self.span(from, to) + 1;
+        if 2 * len <= n {
+            self.reverse_positions(self.pos[from], len);
+        } else {
+            self.reverse_positions(self.pos[to] + 1, n - len);
+        }
+    }
+
+    /// 2-opt move removing (a, succ a) and (c, succ c), adding (a, c) and (succ a, succ c)
+    pub fn two_opt_move(&mut self, a: usize, c: usize) {
+        let b = self.succ(a);
+        self.reverse_path(b, c);
+    }
+
+    /// Length change of `two_opt_move(a, c)`
+    pub fn two_opt_delta(&self, a: usize, c: usize, cities: &[City]) -> f64 {
+        let (b, d) = (self.succ(a), self.succ(c));
+        let dist = |x: usize, y: usize| euclidean_distance(&cities[x], &cities[y]);
+        dist(a, c) + dist(b, d) - dist(a, b) - dist(c, d)
+    }
+
+    /// Move the `seg_len` cities starting at `s1` between `left` and `succ(left)`,
+    /// optionally reversed. Done as three reversals around the shorter side.
+    /// `left` must lie outside the segment and must not be its predecessor.
+    pub fn move_segment(&mut self, s1: usize, seg_len: usize, left: usize, reversed: bool) {
+        let n = self.order.len();
+        let i = self.pos[s1];
+        let ahead = self.span(s1, left) + 1 - seg_len; // cities between segment end and `left`
+        let behind = n - seg_len - ahead; // cities between `succ(left)` and segment start
+
+        if ahead <= behind {
+            // p S X e -> p X S e
+            self.reverse_positions(i, seg_len + ahead);
+            self.reverse_positions(i, ahead);
+            if !reversed {
+                self.reverse_positions(i + ahead, seg_len);
+            }
+        } else {
+            // c Z S nx -> c S Z nx
+            let j = (i + n - behind) % n;
+            self.reverse_positions(j, behind + seg_len);
+            self.reverse_positions(j + seg_len, behind);
+            if !reversed {
+                self.reverse_positions(j, seg_len);
+            }
+        }
+    }
+
+    /// Length change of `move_segment(s1, seg_len, left, reversed)`
+    pub fn move_segment_delta(&self, s1: usize, seg_len: usize, left: usize, reversed: bool, cities: &[City]) -> f64 {
+        let n = self.order.len();
+        let s2 = self.order[(self.pos[s1] + seg_len - 1) % n];
+        let (p, nx) = (self.pred(s1), self.succ(s2));
+        let right = self.succ(left);
+        let (first, last) = if reversed { (s2, s1) } else { (s1, s2) };
+        let dist = |x: usize, y: usize| euclidean_distance(&cities[x], &cities[y]);
+        dist(p, nx) + dist(left, first) + dist(last, right)
+            - dist(p, s1) - dist(s2, nx) - dist(left, right)
+    }
+}
+
+/// Extra cost added to an edge's length, e.g. guided local search penalties
+pub type EdgePenalty<'a> = &'a dyn Fn(usize, usize) -> f64;
+
+struct Search<'a> {
+    cities: &'a [City],
+    penalty: Option<EdgePenalty<'a>>,
+    neighbors: &'a [Vec<usize>],
+    t: &'a mut ArrayTour,
+    queue: VecDeque<usize>,
+    queued: Vec<bool>,
+    /// Sum of the true length changes of all applied moves
+    delta: f64,
+}
+
+impl Search<'_> {
+    /// True length of edge (a, b): the order of the neighbour lists
+    fn dist(&self, a: usize, b: usize) -> f64 {
+        euclidean_distance(&self.cities[a], &self.cities[b])
+    }
+
+    /// Length of edge (a, b) plus its penalty
+    fn d(&self, a: usize, b: usize) -> f64 {
+        match self.penalty {
+            Some(penalty) => self.dist(a, b) + penalty(a, b),
+            None => self.dist(a, b),
+        }
+    }
+
+    /// True length change of a move that swaps edges `removed` for `added`;
+    /// `delta` is its change under `d`, which is the same without penalties
+    fn length_change(&self, delta: f64, added: &[(usize, usize)], removed: &[(usize, usize)]) -> f64 {
+        if self.penalty.is_none() {
+            return delta;
+        }
+        let sum = |edges: &[(usize, usize)]| edges.iter().map(|&(a, b)| self.dist(a, b)).sum::<f64>();
+        sum(added) - sum(removed)
+    }
+
+    fn succ(&self, c: usize) -> usize {
+        self.t.succ(c)
+    }
+
+    fn pred(&self, c: usize) -> usize {
+        self.t.pred(c)
+    }
+
+    fn span(&self, a: usize, b: usize) -> usize {
+        self.t.span(a, b)
+    }
+
+    fn activate(&mut self, c: usize) {
+        if !self.queued[c] {
+            self.queued[c] = true;
+            self.queue.push_back(c);
+        }
+    }
+
+    /// Try an improving 2-opt move that adds an edge (a, c) for a candidate c
+    fn try_two_opt(&mut self, a: usize) -> bool {
+        for forward in [true, false] {
+            let b = if forward { self.succ(a) } else { self.pred(a) };
+            let d_ab = self.d(a, b);
+            for idx in 0..self.neighbors[a].len() {
+                let c = self.neighbors[a][idx];
+                let d_ac = self.d(a, c);
+                if d_ac >= d_ab {
+                    // Penalties are >= 0: once the true distance reaches d_ab, no
+                    // later candidate can have d_ac < d_ab
+                    if self.penalty.is_none() || self.dist(a, c) >= d_ab {
+                        break;
+                    }
+                    continue;
+                }
+                let d = if forward { self.succ(c) } else { self.pred(c) };
+                if c == b || d == a {
+                    continue;
+                }
+                let delta = d_ac + self.d(b, d) - d_ab - self.d(c, d);
+                if delta < -EPS {
+                    self.delta += self.length_change(delta, &[(a, c), (b, d)], &[(a, b), (c, d)]);
+                    // forward: a b .. c d -> a c .. b d ; backward: d c .. b a -> d b .. c a
+                    let (from, to) = if forward { (b, c) } else { (c, b) };
+                    self.t.reverse_path(from, to);
+                    for x in [a, b, c, d] {
+                        self.activate(x);
+                    }
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    /// Try moving a segment of 1..=3 cities starting at `a` next to one of its neighbours
+    fn try_or_opt(&mut self, a: usize) -> bool {
+        let n = self.t.len();
+        for seg_len in 1..=3usize {
+            if seg_len + 3 > n {
+                break;
+            }
+            let s1 = a;
+            let s2 = self.t.order[(self.t.pos[a] + seg_len - 1) % n];
+            let p = self.pred(s1);
+            let nx = self.succ(s2);
+            let removal_gain = self.d(p, s1) + self.d(s2, nx) - self.d(p, nx);
+            if removal_gain <= EPS {
+                continue;
+            }
+
+            for idx in 0..self.neighbors[s1].len() {
+                let c = self.neighbors[s1][idx];
+                if self.d(c, s1) >= removal_gain {
+                    if self.penalty.is_none() || self.dist(c, s1) >= removal_gain {
+                        break;
+                    }
+                    continue;
+                }
+                if self.span(s1, c) < seg_len {
+                    continue; // c lies inside the segment
+                }
+                // Place s1 next to c: either c s1..s2 succ(c), or pred(c) s2..s1 c
+                for c_is_left in [true, false] {
+                    let (left, right) = if c_is_left { (c, self.succ(c)) } else { (self.pred(c), c) };
+                    if self.span(s1, left) < seg_len || self.span(s1, right) < seg_len {
+                        continue;
+                    }
+                    if left == p || right == nx {
+                        continue; // edge adjacent to the segment itself
+                    }
+                    let (first, last) = if c_is_left { (s1, s2) } else { (s2, s1) };
+                    let delta = self.d(left, first) + self.d(last, right) - self.d(left, right)
+                        - removal_gain;
+                    if delta < -EPS {
+                        self.delta += self.length_change(
+                            delta,
+                            &[(left, first), (last, right), (p, nx)],
+                            &[(left, right), (p, s1), (s2, nx)],
+                        );
+                        self.t.move_segment(s1, seg_len, left, !c_is_left);
+                        for x in [p, nx, left, right, s1, s2] {
+                            self.activate(x);
+                        }
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+}
+
+/// Improve `tour` in place. `budget` caps the number of city activations
+/// (0 = run to a local optimum). Returns the number of improving moves applied.
+pub fn local_search(
+    tour: &mut Vec<usize>,
+    cities: &[City],
+    neighbors: &[Vec<usize>],
+    kind: LocalSearchKind,
+    budget: usize,
+) -> usize {
+    let mut t = ArrayTour::new(std::mem::take(tour));
+    let (moves, _) = improve(&mut t, cities, neighbors, kind, budget, None);
+    *tour = t.order;
+    moves
+}
+
+/// Local search on an `ArrayTour`. With `active = Some(cities)` only those cities
+/// start with their don't-look bit cleared (e.g. the endpoints of a kick), so the
+/// cost is proportional to the part of the tour that changed.
+/// Returns (improving moves applied, total length change).
+pub fn improve(
+    t: &mut ArrayTour,
+    cities: &[City],
+    neighbors: &[Vec<usize>],
+    kind: LocalSearchKind,
+    budget: usize,
+    active: Option<&[usize]>,
+) -> (usize, f64) {
+    run(t, cities, neighbors, kind, budget, active, None)
+}
+
+/// `improve` under an augmented objective: every edge (a, b) costs
+/// `dist(a, b) + penalty(a, b)`. The returned length change is the true one,
+/// not the augmented one.
+/// `penalty` must be non-negative: the candidate scans stop on the true distance,
+/// which the neighbour lists are sorted by.
+pub fn improve_penalized(
+    t: &mut ArrayTour,
+    cities: &[City],
+    neighbors: &[Vec<usize>],
+    kind: LocalSearchKind,
+    active: Option<&[usize]>,
+    penalty: EdgePenalty,
+) -> (usize, f64) {
+    run(t, cities, neighbors, kind, 0, active, Some(penalty))
+}
+
+fn run(
+    t: &mut ArrayTour,
+    cities: &[City],
+    neighbors: &[Vec<usize>],
+    kind: LocalSearchKind,
+    budget: usize,
+    active: Option<&[usize]>,
+    penalty: Option<EdgePenalty>,
+) -> (usize, f64) {
+    let n = t.len();
+    if n < 5 {
+        return (0, 0.0);
+    }
+    let mut queued = vec![false; n];
+    let queue: VecDeque<usize> = match active {
+        Some(active) => active.iter().copied().filter(|&c| !std::mem::replace(&mut queued[c], true)).collect(),
+        None => {
+            queued.fill(true);
+            t.order.iter().copied().collect()
+        }
+    };
+    let mut search = Search { cities, penalty, neighbors, t, queue, queued, delta: 0.0 };
+
+    let mut moves = 0;
+    let mut activations = 0;
+    while let Some(a) = search.queue.pop_front() {
+        if budget > 0 && activations >= budget {
+            break;
+        }
+        activations += 1;
+        search.queued[a] = false;
+
+        let improved = match kind {
+            LocalSearchKind::TwoOpt => search.try_two_opt(a),
+            LocalSearchKind::OrOpt => search.try_or_opt(a),
+            LocalSearchKind::Both => search.try_two_opt(a) || search.try_or_opt(a),
+        };
+        if improved {
+            moves += 1;
+            search.activate(a);
+        }
+    }
+    (moves, search.delta)
+}
@@ -0,0 +1,168 @@
+/// Held–Karp 1-tree lower bound with subgradient optimisation
+///
+/// A 1-tree is a spanning tree on cities 1..n plus the two cheapest edges at
+/// city 0; every tour is a 1-tree, so the cheapest 1-tree bounds the optimum
+/// from below. With node penalties `pi` added to the edge costs
+/// (`d(a, b) + pi[a] + pi[b]`) every tour gains exactly `2 * sum(pi)`, so
+/// `1-tree(pi) - 2 * sum(pi)` is a lower bound for any `pi`. Subgradient ascent
+/// raises the penalty of cities with degree > 2 and lowers it at leaves, with
+/// Polyak steps `lambda * (upper - bound) / |degree - 2|^2`; `lambda` is halved
+/// whenever the bound stops improving.
+///
+/// The 1-tree is built on the complete graph with Prim's algorithm (a tree on
+/// candidate edges only would not be a valid bound). From `PAR_MST_MIN` cities
+/// on, and with more than one rayon thread, each Prim step (key updates and
+/// selection of the next city) runs in parallel.
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::construction::nearest_neighbor_tour;
+use crate::all_versions::utils::*;
+
+/// Cities from which Prim's inner loops run on rayon
+const PAR_MST_MIN: usize = 4096;
+
+#[derive(Clone, Debug)]
+pub struct LowerBoundConfig {
+    /// Initial step factor
+    pub lambda: f64,
+    /// Halve `lambda` after this many iterations without improving the bound
+    /// (0 = n)
+    pub halve_after: usize,
+    /// One iteration = one 1-tree
+    pub stop: StopCondition,
+}
+
+impl Default for LowerBoundConfig {
+    fn default() -> Self {
+        Self {
+            lambda: 2.0,
+            halve_after: 15,
+            stop: StopCondition { max_iterations: 300, time_limit: None, patience: 100 },
+        }
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct LowerBound {
+    pub bound: f64,
+    /// Node penalties of the best bound
+    pub penalties: Vec<f64>,
+    pub iterations: usize,
+    /// True if the best 1-tree is a tour (the bound is then the optimum)
+    pub is_tour: bool,
+}
+
+/// Minimum 1-tree under penalties `pi`: returns its penalised weight and
+/// fills `degree` with the node degrees
+fn one_tree(n: usize, d: &(impl Fn(usize, usize) -> f64 + Sync), pi: &[f64], degree: &mut [i32]) -> f64 {
+    let w = |a: usize, b: usize| d(a, b) + pi[a] + pi[b];
+    degree.iter_mut().for_each(|g| *g = 0);
+
+    // Prim over 1..n, starting from city 1. Each step relaxes the keys against
+    // the city just added and picks the next one in the same pass.
+    let mut in_tree = vec![false; n];
+    let mut key = vec![f64::INFINITY; n];
+    let mut from = vec![1; n];
+    in_tree[0] = true;
+    let parallel = n >= PAR_MST_MIN && rayon::current_num_threads() > 1;
+    let mut total = 0.0;
+    let mut v = 1;
+    for _ in 1..n {
+        in_tree[v] = true;
+        let relax = |(u, (key_u, from_u)): (usize, (&mut f64, &mut usize))| {
+            if in_tree[u] {
+                return (f64::INFINITY, usize::MAX);
+            }
+            let c = w(v, u);
+            if c < *key_u {
+                *key_u = c;
+                *from_u = v;
+            }
+            (*key_u, u)
+        };
+        let closer = |a: (f64, usize), b: (f64, usize)| if (a.0, a.1) <= (b.0, b.1) { a } else { b };
+        let (k, next) = if parallel {
+            key.par_iter_mut().zip(from.par_iter_mut()).enumerate().map(relax).reduce(|| (f64::INFINITY, usize::MAX), closer)
+        } else {
+            key.iter_mut().zip(from.iter_mut()).enumerate().map(relax).fold((f64::INFINITY, usize::MAX), closer)
+        };
+        if next == usize::MAX {
+            break;
+        }
+        total += k;
+        degree[next] += 1;
+        degree[from[next]] += 1;
+        v = next;
+    }
+
+    // The two cheapest edges at city 0
+    let (mut first, mut second) = ((f64::INFINITY, 0), (f64::INFINITY, 0));
+    for v in 1..n {
+        let c = w(0, v);
+        if c < first.0 {
+            second = first;
+            first = (c, v);
+        } else if c < second.0 {
+            second = (c, v);
+        }
+    }
+    degree[0] = 2;
+    degree[first.1] += 1;
+    degree[second.1] += 1;
+    total + first.0 + second.0
+}
+
+/// 1-tree bound with subgradient ascent for `n` cities under distance `d`;
+/// `upper` is the length of any tour (used for the step size)
+pub fn held_karp_bound_with(n: usize, d: impl Fn(usize, usize) -> f64 + Sync, upper: f64, config: &LowerBoundConfig) -> LowerBound {
+    if n < 3 {
+        return LowerBound { bound: upper, penalties: vec![0.0; n], iterations: 0, is_tour: true };
+    }
+    let start = Instant::now();
+    let halve_after = if config.halve_after == 0 { n } else { config.halve_after };
+    let mut pi = vec![0.0; n];
+    let mut degree = vec![0i32; n];
+    let mut best = LowerBound { bound: f64::NEG_INFINITY, penalties: pi.clone(), iterations: 0, is_tour: false };
+    let mut lambda = config.lambda;
+
+    let mut iteration = 0;
+    let mut since_improvement = 0;
+    let mut since_halving = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) && lambda > 1e-6 {
+        let bound = one_tree(n, &d, &pi, &mut degree) - 2.0 * pi.iter().sum::<f64>();
+        let norm: i32 = degree.iter().map(|&g| (g - 2) * (g - 2)).sum();
+        iteration += 1;
+
+        if bound > best.bound + 1e-9 {
+            best.bound = bound;
+            best.penalties.copy_from_slice(&pi);
+            best.is_tour = norm == 0;
+            since_improvement = 0;
+            since_halving = 0;
+        } else {
+            since_improvement += 1;
+            since_halving += 1;
+            if since_halving >= halve_after {
+                lambda /= 2.0;
+                since_halving = 0;
+            }
+        }
+        if norm == 0 {
+            break;
+        }
+        let step = lambda * (upper - bound).max(0.0) / norm as f64;
+        for (p, &g) in pi.iter_mut().zip(&degree) {
+            *p += step * (g - 2) as f64;
+        }
+    }
+    best.iterations = iteration;
+    best
+}
+
+/// 1-tree bound for `cities`, with a nearest-neighbour tour as the upper bound
+#[allow(dead_code)]
+pub fn held_karp_bound(cities: &[City], config: &LowerBoundConfig) -> LowerBound {
+    let upper = if cities.is_empty() { 0.0 } else { compute_total_distance(&nearest_neighbor_tour(0, cities), cities) };
+    held_karp_bound_with(cities.len(), |a, b| euclidean_distance(&cities[a], &cities[b]), upper, config)
+}
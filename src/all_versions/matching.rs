@@ -0,0 +1,524 @@
+/// Minimum-weight perfect matching (Edmonds' blossom algorithm)
+///
+/// `min_weight_perfect_matching` pairs up an even set of cities so that the
+/// summed pair length is minimal, the matching step of Christofides. It runs
+/// the weighted blossom algorithm with dual variables (Galil's O(m³) version,
+/// following Van Rantwijk's `mwmatching`) as a maximum-weight maximum-cardinality
+/// matching on the complete graph with weights `K - length`.
+///
+/// Lengths are scaled to integers (the longest pair becomes `LENGTH_SCALE`)
+/// so that every dual update is exact; the matching is optimal for the rounded
+/// lengths, which differ from the true ones by at most 1 / `LENGTH_SCALE` of
+/// the longest pair.
+use crate::all_versions::utils::*;
+
+/// Integer length of the longest pair
+const LENGTH_SCALE: f64 = 1e9;
+/// Missing vertex, edge end, blossom or label end
+const NONE: usize = usize::MAX;
+
+/// Pairs of `points` (even count) with the smallest total length; every point
+/// appears in exactly one pair
+#[allow(dead_code)]
+pub fn min_weight_perfect_matching(cities: &[City], points: &[usize]) -> Vec<(usize, usize)> {
+    let m = points.len();
+    assert!(m.is_multiple_of(2), "a perfect matching needs an even number of points");
+    if m == 0 {
+        return Vec::new();
+    }
+    let d = |i: usize, j: usize| euclidean_distance(&cities[points[i]], &cities[points[j]]);
+    let longest = (0..m).flat_map(|i| (i + 1..m).map(move |j| (i, j))).map(|(i, j)| d(i, j)).fold(0.0, f64::max);
+    let scale = if longest > 0.0 { LENGTH_SCALE / longest } else { 0.0 };
+    let top = LENGTH_SCALE as i64 + 1;
+    let edges: Vec<(usize, usize, i64)> = (0..m)
+        .flat_map(|i| (i + 1..m).map(move |j| (i, j)))
+        .map(|(i, j)| (i, j, top - (d(i, j) * scale).round() as i64))
+        .collect();
+
+    let mate = Blossom::new(m, edges).solve();
+    (0..m).filter(|&i| i < mate[i]).map(|i| (points[i], points[mate[i]])).collect()
+}
+
+/// State of the weighted blossom algorithm on `n` vertices. Vertices are
+/// `0..n`, blossoms `n..2n`; edge `k` has endpoints `2k` and `2k + 1`.
+struct Blossom {
+    n: usize,
+    edges: Vec<(usize, usize, i64)>,
+    /// `endpoint[p]`: vertex at endpoint `p`
+    endpoint: Vec<usize>,
+    /// Endpoints of the edges incident to each vertex, pointing away from it
+    neighbend: Vec<Vec<usize>>,
+    /// Remote endpoint of each vertex's matched edge
+    mate: Vec<usize>,
+    /// 0 = free, 1 = S, 2 = T (5 marks S blossoms during `scan_blossom`)
+    label: Vec<u8>,
+    /// Endpoint through which a vertex or blossom got its label
+    labelend: Vec<usize>,
+    /// Top-level blossom containing each vertex
+    inblossom: Vec<usize>,
+    blossomparent: Vec<usize>,
+    blossomchilds: Vec<Vec<usize>>,
+    blossombase: Vec<usize>,
+    /// `blossomendps[b][i]`: endpoint joining child `i` to child `i + 1`
+    blossomendps: Vec<Vec<usize>>,
+    /// Least-slack edge to an S vertex (vertices) or S blossom (S blossoms)
+    bestedge: Vec<usize>,
+    blossombestedges: Vec<Option<Vec<usize>>>,
+    unusedblossoms: Vec<usize>,
+    dualvar: Vec<i64>,
+    allowedge: Vec<bool>,
+    queue: Vec<usize>,
+}
+
+impl Blossom {
+    fn new(n: usize, edges: Vec<(usize, usize, i64)>) -> Self {
+        let maxweight = edges.iter().map(|e| e.2).max().unwrap_or(0).max(0);
+        let endpoint = (0..2 * edges.len()).map(|p| if p % 2 == 0 { edges[p / 2].0 } else { edges[p / 2].1 }).collect();
+        let mut neighbend = vec![Vec::new(); n];
+        for (k, &(i, j, _)) in edges.iter().enumerate() {
+            neighbend[i].push(2 * k + 1);
+            neighbend[j].push(2 * k);
+        }
+        let mut dualvar = vec![maxweight; n];
+        dualvar.resize(2 * n, 0);
+        let mut blossombase: Vec<usize> = (0..n).collect();
+        blossombase.resize(2 * n, NONE);
+        Self {
+            n,
+            endpoint,
+            neighbend,
+            mate: vec![NONE; n],
+            label: vec![0; 2 * n],
+            labelend: vec![NONE; 2 * n],
+            inblossom: (0..n).collect(),
+            blossomparent: vec![NONE; 2 * n],
+            blossomchilds: vec![Vec::new(); 2 * n],
+            blossombase,
+            blossomendps: vec![Vec::new(); 2 * n],
+            bestedge: vec![NONE; 2 * n],
+            blossombestedges: vec![None; 2 * n],
+            unusedblossoms: (n..2 * n).collect(),
+            dualvar,
+            allowedge: vec![false; edges.len()],
+            queue: Vec::new(),
+            edges,
+        }
+    }
+
+    fn slack(&self, k: usize) -> i64 {
+        let (i, j, w) = self.edges[k];
+        self.dualvar[i] + self.dualvar[j] - 2 * w
+    }
+
+    fn leaves(&self, b: usize) -> Vec<usize> {
+        let mut out = Vec::new();
+        let mut stack = vec![b];
+        while let Some(t) = stack.pop() {
+            if t < self.n {
+                out.push(t);
+            } else {
+                stack.extend(self.blossomchilds[t].iter().rev());
+            }
+        }
+        out
+    }
+
+    /// Index `j` (possibly negative) into the cyclic child list of `b`
+    fn child(&self, b: usize, j: isize) -> usize {
+        let len = self.blossomchilds[b].len() as isize;
+        self.blossomchilds[b][j.rem_euclid(len) as usize]
+    }
+
+    fn endp(&self, b: usize, j: isize) -> usize {
+        let len = self.blossomendps[b].len() as isize;
+        self.blossomendps[b][j.rem_euclid(len) as usize]
+    }
+
+    /// Label `w` and its top-level blossom with `t`, reached through endpoint `p`
+    fn assign_label(&mut self, w: usize, t: u8, p: usize) {
+        let b = self.inblossom[w];
+        debug_assert!(self.label[w] == 0 && self.label[b] == 0);
+        self.label[w] = t;
+        self.label[b] = t;
+        self.labelend[w] = p;
+        self.labelend[b] = p;
+        self.bestedge[w] = NONE;
+        self.bestedge[b] = NONE;
+        if t == 1 {
+            let leaves = self.leaves(b);
+            self.queue.extend(leaves);
+        } else {
+            let base = self.blossombase[b];
+            let mb = self.mate[base];
+            self.assign_label(self.endpoint[mb], 1, mb ^ 1);
+        }
+    }
+
+    /// Trace back from S vertices `v` and `w`: the base of the new blossom, or
+    /// `NONE` if the paths reach two different roots (an augmenting path)
+    fn scan_blossom(&mut self, mut v: usize, mut w: usize) -> usize {
+        let mut path = Vec::new();
+        let mut base = NONE;
+        while v != NONE || w != NONE {
+            let b = self.inblossom[v];
+            if self.label[b] & 4 != 0 {
+                base = self.blossombase[b];
+                break;
+            }
+            path.push(b);
+            self.label[b] = 5;
+            if self.labelend[b] == NONE {
+                v = NONE;
+            } else {
+                v = self.endpoint[self.labelend[b]];
+                let b = self.inblossom[v];
+                v = self.endpoint[self.labelend[b]];
+            }
+            if w != NONE {
+                std::mem::swap(&mut v, &mut w);
+            }
+        }
+        for b in path {
+            self.label[b] = 1;
+        }
+        base
+    }
+
+    /// Contract the cycle closed by edge `k` through `base` into a new S blossom
+    fn add_blossom(&mut self, base: usize, k: usize) {
+        let (mut v, mut w, _) = self.edges[k];
+        let bb = self.inblossom[base];
+        let mut bv = self.inblossom[v];
+        let mut bw = self.inblossom[w];
+        let b = self.unusedblossoms.pop().expect("at most n blossoms");
+        self.blossombase[b] = base;
+        self.blossomparent[b] = NONE;
+        self.blossomparent[bb] = b;
+        let mut path = Vec::new();
+        let mut endps = Vec::new();
+        while bv != bb {
+            self.blossomparent[bv] = b;
+            path.push(bv);
+            endps.push(self.labelend[bv]);
+            v = self.endpoint[self.labelend[bv]];
+            bv = self.inblossom[v];
+        }
+        path.push(bb);
+        path.reverse();
+        endps.reverse();
+        endps.push(2 * k);
+        while bw != bb {
+            self.blossomparent[bw] = b;
+            path.push(bw);
+            endps.push(self.labelend[bw] ^ 1);
+            w = self.endpoint[self.labelend[bw]];
+            bw = self.inblossom[w];
+        }
+        self.label[b] = 1;
+        self.labelend[b] = self.labelend[bb];
+        self.dualvar[b] = 0;
+        for v in self.leaves_of(&path) {
+            if self.label[self.inblossom[v]] == 2 {
+                self.queue.push(v);
+            }
+            self.inblossom[v] = b;
+        }
+
+        let mut bestedgeto = vec![NONE; 2 * self.n];
+        for &bv in &path {
+            let lists: Vec<Vec<usize>> = match self.blossombestedges[bv].take() {
+                Some(list) => vec![list],
+                None => self.leaves(bv).into_iter().map(|v| self.neighbend[v].iter().map(|p| p / 2).collect()).collect(),
+            };
+            for k in lists.into_iter().flatten() {
+                // the end of edge k outside the new blossom, if any
+                let (i, j, _) = self.edges[k];
+                let j = if self.inblossom[j] == b { i } else { j };
+                let bj = self.inblossom[j];
+                if bj != b && self.label[bj] == 1 && (bestedgeto[bj] == NONE || self.slack(k) < self.slack(bestedgeto[bj])) {
+                    bestedgeto[bj] = k;
+                }
+            }
+            self.bestedge[bv] = NONE;
+        }
+        let best: Vec<usize> = bestedgeto.into_iter().filter(|&k| k != NONE).collect();
+        self.bestedge[b] = best.iter().copied().min_by_key(|&k| self.slack(k)).unwrap_or(NONE);
+        self.blossombestedges[b] = Some(best);
+        self.blossomchilds[b] = path;
+        self.blossomendps[b] = endps;
+    }
+
+    fn leaves_of(&self, children: &[usize]) -> Vec<usize> {
+        children.iter().flat_map(|&c| self.leaves(c)).collect()
+    }
+
+    /// Undo blossom `b`; mid-stage, relabel its children to keep the alternating tree
+    fn expand_blossom(&mut self, b: usize, endstage: bool) {
+        for s in self.blossomchilds[b].clone() {
+            self.blossomparent[s] = NONE;
+            if s < self.n {
+                self.inblossom[s] = s;
+            } else if endstage && self.dualvar[s] == 0 {
+                self.expand_blossom(s, endstage);
+            } else {
+                for v in self.leaves(s) {
+                    self.inblossom[v] = s;
+                }
+            }
+        }
+        if !endstage && self.label[b] == 2 {
+            let entrychild = self.inblossom[self.endpoint[self.labelend[b] ^ 1]];
+            let len = self.blossomchilds[b].len() as isize;
+            let mut j = self.blossomchilds[b].iter().position(|&c| c == entrychild).unwrap() as isize;
+            let (jstep, endptrick) = if j & 1 == 1 {
+                j -= len;
+                (1, 0)
+            } else {
+                (-1, 1)
+            };
+            let mut p = self.labelend[b];
+            while j != 0 {
+                self.label[self.endpoint[p ^ 1]] = 0;
+                let q = self.endp(b, j - endptrick) ^ endptrick as usize ^ 1;
+                self.label[self.endpoint[q]] = 0;
+                self.assign_label(self.endpoint[p ^ 1], 2, p);
+                let k = self.endp(b, j - endptrick) / 2;
+                self.allowedge[k] = true;
+                j += jstep;
+                p = self.endp(b, j - endptrick) ^ endptrick as usize;
+                self.allowedge[p / 2] = true;
+                j += jstep;
+            }
+            let bv = self.child(b, j);
+            let ep = self.endpoint[p ^ 1];
+            self.label[ep] = 2;
+            self.label[bv] = 2;
+            self.labelend[ep] = p;
+            self.labelend[bv] = p;
+            self.bestedge[bv] = NONE;
+            j += jstep;
+            while self.child(b, j) != entrychild {
+                let bv = self.child(b, j);
+                if self.label[bv] == 1 {
+                    j += jstep;
+                    continue;
+                }
+                if let Some(v) = self.leaves(bv).into_iter().find(|&v| self.label[v] != 0) {
+                    self.label[v] = 0;
+                    let mb = self.mate[self.blossombase[bv]];
+                    self.label[self.endpoint[mb]] = 0;
+                    self.assign_label(v, 2, self.labelend[v]);
+                }
+                j += jstep;
+            }
+        }
+        self.label[b] = 0;
+        self.labelend[b] = NONE;
+        self.blossomchilds[b] = Vec::new();
+        self.blossomendps[b] = Vec::new();
+        self.blossombase[b] = NONE;
+        self.blossombestedges[b] = None;
+        self.bestedge[b] = NONE;
+        self.unusedblossoms.push(b);
+    }
+
+    /// Swap matched and unmatched edges on the path from `v` to the base of `b`
+    fn augment_blossom(&mut self, b: usize, v: usize) {
+        let mut t = v;
+        while self.blossomparent[t] != b {
+            t = self.blossomparent[t];
+        }
+        if t >= self.n {
+            self.augment_blossom(t, v);
+        }
+        let i = self.blossomchilds[b].iter().position(|&c| c == t).unwrap();
+        let len = self.blossomchilds[b].len() as isize;
+        let mut j = i as isize;
+        let (jstep, endptrick) = if i & 1 == 1 {
+            j -= len;
+            (1, 0)
+        } else {
+            (-1, 1)
+        };
+        while j != 0 {
+            j += jstep;
+            let t = self.child(b, j);
+            let p = self.endp(b, j - endptrick) ^ endptrick as usize;
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p]);
+            }
+            j += jstep;
+            let t = self.child(b, j);
+            if t >= self.n {
+                self.augment_blossom(t, self.endpoint[p ^ 1]);
+            }
+            self.mate[self.endpoint[p]] = p ^ 1;
+            self.mate[self.endpoint[p ^ 1]] = p;
+        }
+        self.blossomchilds[b].rotate_left(i);
+        self.blossomendps[b].rotate_left(i);
+        self.blossombase[b] = self.blossombase[self.blossomchilds[b][0]];
+        debug_assert_eq!(self.blossombase[b], v);
+    }
+
+    /// Augment along the path through edge `k` between two S vertices
+    fn augment_matching(&mut self, k: usize) {
+        let (v, w, _) = self.edges[k];
+        for (mut s, mut p) in [(v, 2 * k + 1), (w, 2 * k)] {
+            loop {
+                let bs = self.inblossom[s];
+                if bs >= self.n {
+                    self.augment_blossom(bs, s);
+                }
+                self.mate[s] = p;
+                if self.labelend[bs] == NONE {
+                    break;
+                }
+                let t = self.endpoint[self.labelend[bs]];
+                let bt = self.inblossom[t];
+                s = self.endpoint[self.labelend[bt]];
+                let j = self.endpoint[self.labelend[bt] ^ 1];
+                if bt >= self.n {
+                    self.augment_blossom(bt, j);
+                }
+                self.mate[j] = self.labelend[bt];
+                p = self.labelend[bt] ^ 1;
+            }
+        }
+    }
+
+    /// Maximum-weight maximum-cardinality matching: `mate[v]` for every vertex
+    fn solve(mut self) -> Vec<usize> {
+        let n = self.n;
+        for _ in 0..n {
+            self.label.fill(0);
+            self.bestedge.fill(NONE);
+            for b in n..2 * n {
+                self.blossombestedges[b] = None;
+            }
+            self.allowedge.fill(false);
+            self.queue.clear();
+            for v in 0..n {
+                if self.mate[v] == NONE && self.label[self.inblossom[v]] == 0 {
+                    self.assign_label(v, 1, NONE);
+                }
+            }
+
+            let mut augmented = false;
+            loop {
+                while !augmented {
+                    let Some(v) = self.queue.pop() else { break };
+                    for idx in 0..self.neighbend[v].len() {
+                        let p = self.neighbend[v][idx];
+                        let k = p / 2;
+                        let w = self.endpoint[p];
+                        if self.inblossom[v] == self.inblossom[w] {
+                            continue;
+                        }
+                        let mut kslack = 0;
+                        if !self.allowedge[k] {
+                            kslack = self.slack(k);
+                            if kslack <= 0 {
+                                self.allowedge[k] = true;
+                            }
+                        }
+                        if self.allowedge[k] {
+                            if self.label[self.inblossom[w]] == 0 {
+                                self.assign_label(w, 2, p ^ 1);
+                            } else if self.label[self.inblossom[w]] == 1 {
+                                let base = self.scan_blossom(v, w);
+                                if base != NONE {
+                                    self.add_blossom(base, k);
+                                } else {
+                                    self.augment_matching(k);
+                                    augmented = true;
+                                    break;
+                                }
+                            } else if self.label[w] == 0 {
+                                self.label[w] = 2;
+                                self.labelend[w] = p ^ 1;
+                            }
+                        } else if self.label[self.inblossom[w]] == 1 {
+                            let b = self.inblossom[v];
+                            if self.bestedge[b] == NONE || kslack < self.slack(self.bestedge[b]) {
+                                self.bestedge[b] = k;
+                            }
+                        } else if self.label[w] == 0 && (self.bestedge[w] == NONE || kslack < self.slack(self.bestedge[w])) {
+                            self.bestedge[w] = k;
+                        }
+                    }
+                }
+                if augmented {
+                    break;
+                }
+
+                // No tight edge left: find the largest dual step that keeps all slacks >= 0
+                let mut delta: Option<(i64, u8, usize)> = None;
+                let better = |delta: &Option<(i64, u8, usize)>, d: i64| delta.is_none_or(|(best, _, _)| d < best);
+                for v in 0..n {
+                    if self.label[self.inblossom[v]] == 0 && self.bestedge[v] != NONE {
+                        let d = self.slack(self.bestedge[v]);
+                        if better(&delta, d) {
+                            delta = Some((d, 2, self.bestedge[v]));
+                        }
+                    }
+                }
+                for b in 0..2 * n {
+                    if self.blossomparent[b] == NONE && self.label[b] == 1 && self.bestedge[b] != NONE {
+                        let kslack = self.slack(self.bestedge[b]);
+                        debug_assert!(kslack % 2 == 0);
+                        if better(&delta, kslack / 2) {
+                            delta = Some((kslack / 2, 3, self.bestedge[b]));
+                        }
+                    }
+                }
+                for b in n..2 * n {
+                    if self.blossombase[b] != NONE && self.blossomparent[b] == NONE && self.label[b] == 2 && better(&delta, self.dualvar[b]) {
+                        delta = Some((self.dualvar[b], 4, b));
+                    }
+                }
+                let (delta, kind, target) = delta.unwrap_or_else(|| (self.dualvar[..n].iter().copied().min().unwrap().max(0), 1, NONE));
+
+                for v in 0..n {
+                    match self.label[self.inblossom[v]] {
+                        1 => self.dualvar[v] -= delta,
+                        2 => self.dualvar[v] += delta,
+                        _ => {}
+                    }
+                }
+                for b in n..2 * n {
+                    if self.blossombase[b] != NONE && self.blossomparent[b] == NONE {
+                        match self.label[b] {
+                            1 => self.dualvar[b] += delta,
+                            2 => self.dualvar[b] -= delta,
+                            _ => {}
+                        }
+                    }
+                }
+
+                match kind {
+                    1 => break,
+                    2 => {
+                        self.allowedge[target] = true;
+                        let (i, j, _) = self.edges[target];
+                        self.queue.push(if self.label[self.inblossom[i]] == 0 { j } else { i });
+                    }
+                    3 => {
+                        self.allowedge[target] = true;
+                        self.queue.push(self.edges[target].0);
+                    }
+                    _ => self.expand_blossom(target, false),
+                }
+            }
+            if !augmented {
+                break;
+            }
+            for b in n..2 * n {
+                if self.blossomparent[b] == NONE && self.blossombase[b] != NONE && self.label[b] == 1 && self.dualvar[b] == 0 {
+                    self.expand_blossom(b, true);
+                }
+            }
+        }
+        self.mate.iter().map(|&p| if p == NONE { NONE } else { self.endpoint[p] }).collect()
+    }
+}
@@ -10,3 +10,28 @@ pub mod par_topkplus;
 pub mod ga_baseline;
 pub mod ga_config;
 pub mod par_ga;
+pub mod ga_checkpoint;
+pub mod local_search;
+pub mod ga_log;
+pub mod construction;
+pub mod ga_adaptive;
+pub mod simulated_annealing;
+pub mod iterated_local_search;
+pub mod ant_colony;
+pub mod tabu_search;
+pub mod large_neighborhood_search;
+pub mod guided_local_search;
+pub mod neighborhoods;
+pub mod variable_neighborhood_search;
+pub mod portfolio;
+pub mod algorithm_selection;
+pub mod exact;
+pub mod lower_bound;
+pub mod spatial_index;
+pub mod candidate_set;
+pub mod geometric_partition;
+pub mod tour_segment;
+pub mod multilevel;
+pub mod batch_moves;
+pub mod cost_tracker;
+pub mod matching;
@@ -0,0 +1,141 @@
+/// Multilevel TSP solver: coarsen, solve, refine
+///
+/// For very large instances (100k-1M cities), where `two_opt_seq` cannot
+/// finish:
+///
+/// 1. Coarsen: visiting the nodes in Hilbert-curve order, match every
+///    unmatched node with its nearest unmatched node (`spatial_index::KdTree`
+///    with deletions). Each pair becomes a super-node at the size-weighted
+///    centroid, so every level about halves the node count. Repeat until at
+///    most `coarsest` super-nodes remain.
+/// 2. Solve the coarsest level with an existing variant
+///    (`algorithm_selection::Variant`) from a nearest-neighbour tour.
+/// 3. Uncoarsen level by level: each super-node is replaced by its two
+///    children, ordered to be cheaper between the previous city and the next
+///    super-node. The expanded tour is then refined by a few rounds of
+///    `geometric_partition_2opt` (k-d regions), which 2-opts the tour
+///    fragments of all regions in parallel and repairs the boundaries with
+///    2-opt / Or-opt.
+use std::time::Instant;
+use crate::all_versions::algorithm_selection::Variant;
+use crate::all_versions::construction::{nearest_neighbor_tour, space_filling_curve_tour};
+use crate::all_versions::geometric_partition::*;
+use crate::all_versions::spatial_index::KdTree;
+use crate::all_versions::utils::*;
+
+#[derive(Clone, Debug)]
+pub struct MultilevelConfig {
+    /// Stop coarsening at this many super-nodes
+    pub coarsest: usize,
+    /// Solver of the coarsest level
+    pub coarse_solver: Variant,
+    /// Refinement after every uncoarsening step
+    pub refine: GeometricConfig,
+}
+
+impl Default for MultilevelConfig {
+    fn default() -> Self {
+        Self {
+            coarsest: 1000,
+            coarse_solver: Variant::Seq,
+            refine: GeometricConfig {
+                partition: Partition::KdTree,
+                stop: StopCondition { max_iterations: 10, time_limit: None, patience: 2 },
+                ..GeometricConfig::default()
+            },
+        }
+    }
+}
+
+/// One coarsening step: the super-nodes, their weights (cities contained) and
+/// their children on the finer level
+struct Level {
+    points: Vec<City>,
+    weights: Vec<usize>,
+    children: Vec<(usize, Option<usize>)>,
+}
+
+fn coarsen(points: &[City], weights: &[usize]) -> Level {
+    let mut tree = KdTree::new(points);
+    let mut level = Level { points: Vec::new(), weights: Vec::new(), children: Vec::new() };
+    for a in space_filling_curve_tour(points) {
+        if !tree.contains(a) {
+            continue;
+        }
+        tree.remove(a);
+        let pair = tree.nearest_to(&points[a]);
+        let (point, weight) = match pair {
+            Some(b) => {
+                tree.remove(b);
+                let (wa, wb) = (weights[a] as f64, weights[b] as f64);
+                let point = City {
+                    x: (points[a].x * wa + points[b].x * wb) / (wa + wb),
+                    y: (points[a].y * wa + points[b].y * wb) / (wa + wb),
+                };
+                (point, weights[a] + weights[b])
+            }
+            None => (points[a].clone(), weights[a]),
+        };
+        level.points.push(point);
+        level.weights.push(weight);
+        level.children.push((a, pair));
+    }
+    level
+}
+
+/// Replace every super-node of `tour` by its children on the finer level
+fn expand(tour: &[usize], level: &Level, finer: &[City]) -> Vec<usize> {
+    let m = tour.len();
+    let mut out = Vec::with_capacity(2 * m);
+    for (i, &v) in tour.iter().enumerate() {
+        match level.children[v] {
+            (a, None) => out.push(a),
+            (a, Some(b)) => {
+                let next = &level.points[tour[(i + 1) % m]];
+                let prev = out.last().map_or(&level.points[tour[(i + m - 1) % m]], |&p| &finer[p]);
+                let forward = euclidean_distance(prev, &finer[a]) + euclidean_distance(&finer[b], next);
+                let backward = euclidean_distance(prev, &finer[b]) + euclidean_distance(&finer[a], next);
+                if forward <= backward {
+                    out.extend([a, b]);
+                } else {
+                    out.extend([b, a]);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Multilevel coarsen-solve-refine tour of `cities`
+#[allow(dead_code)]
+pub fn multilevel(cities: &[City], config: &MultilevelConfig) -> (Vec<usize>, f64) {
+    if cities.len() < 3 {
+        let tour: Vec<usize> = (0..cities.len()).collect();
+        let cost = if tour.is_empty() { 0.0 } else { compute_total_distance(&tour, cities) };
+        return (tour, cost);
+    }
+    let start = Instant::now();
+    let mut levels: Vec<Level> = Vec::new();
+    while levels.last().map_or(cities.len(), |level| level.points.len()) > config.coarsest.max(3) {
+        let level = match levels.last() {
+            Some(level) => coarsen(&level.points, &level.weights),
+            None => coarsen(cities, &vec![1; cities.len()]),
+        };
+        levels.push(level);
+    }
+
+    let coarsest = levels.last().map_or(cities, |level| &level.points);
+    let initial = nearest_neighbor_tour(0, coarsest);
+    let (mut tour, cost) = config.coarse_solver.run(&initial, coarsest);
+    println!("Level {}: {} nodes, cost = {:.2} ({:.2?})", levels.len(), coarsest.len(), cost, start.elapsed());
+
+    for depth in (0..levels.len()).rev() {
+        let finer = if depth == 0 { cities } else { &levels[depth - 1].points };
+        let expanded = expand(&tour, &levels[depth], finer);
+        let (refined, cost) = geometric_partition_2opt(&expanded, finer, &config.refine);
+        tour = refined;
+        println!("Level {}: {} nodes, cost = {:.2} ({:.2?})", depth, finer.len(), cost, start.elapsed());
+    }
+    let cost = compute_total_distance(&tour, cities);
+    (tour, cost)
+}
@@ -0,0 +1,387 @@
+/// Reusable tour neighbourhoods on plain `Vec<usize>` tours
+///
+/// - `two_opt_gain` / `two_opt_gains` / `reverse_segment`: the index-based
+///   2-opt delta and move shared by `par_prototype`, `batch_moves` (behind
+///   `par_topk`, `par_topkplus` and the `mult*` variants) and `tabu_search`;
+///   `reverse_shorter_side` applies it by reversing the shorter side of the
+///   cycle where the caller does not hold on to positions
+/// - `two_opt_path`: 2-opt on an open path with fixed endpoints, the
+///   independent unit of the decomposition solvers (`geometric_partition`)
+/// - `Neighborhood`: one move type that can find and apply an improving move
+///   (first or best improvement, rows evaluated in parallel) and apply a random
+///   move for shaking. Implemented by `TwoOpt`, `OrOpt`, `ThreeOpt` (segment
+///   exchange, pruned by neighbour lists) and `Swap`; driven by `vns`.
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+use crate::all_versions::local_search::{neighbor_lists, position_index};
+use crate::all_versions::utils::*;
+
+/// Moves must shorten the tour by more than this to count as improving
+const MIN_GAIN: f64 = 1e-9;
+
+/// Reverse `tour[i..=j]`: the 2-opt move replacing edges (i-1, i), (j, j+1)
+/// with (i-1, j), (i, j+1)
+pub fn reverse_segment(tour: &mut [usize], i: usize, j: usize) {
+    tour[i..=j].reverse();
+}
+
+/// The same 2-opt move as `reverse_segment(tour, i, j)` on the cyclic tour,
+/// but reversing the complement `tour[j+1..] + tour[..i]` when that is shorter,
+/// so a move costs at most n / 2 swaps. Cities outside `i..=j` may change
+/// position (including `tour[0]`); only the cycle is preserved.
+pub fn reverse_shorter_side(tour: &mut [usize], i: usize, j: usize) {
+    let n = tour.len();
+    let len = j + 1 - i;
+    if 2 * len <= n {
+        tour[i..=j].reverse();
+        return;
+    }
+    let (mut a, mut b) = ((j + 1) % n, (i + n - 1) % n);
+    for _ in 0..(n - len) / 2 {
+        tour.swap(a, b);
+        a = (a + 1) % n;
+        b = (b + n - 1) % n;
+    }
+}
+
+/// Length saved by `reverse_segment(tour, i, j)` (positive = shorter), for `1 <= i < j < n`
+pub fn two_opt_gain(tour: &[usize], cities: &[City], i: usize, j: usize) -> f64 {
+    let n = tour.len();
+    let a = tour[i - 1];
+    let b = tour[i];
+    let c = tour[j];
+    let d = tour[(j + 1) % n];
+
+    let current_dist = euclidean_distance(&cities[a], &cities[b])
+        + euclidean_distance(&cities[c], &cities[d]);
+    let new_dist = euclidean_distance(&cities[a], &cities[c])
+        + euclidean_distance(&cities[b], &cities[d]);
+
+    current_dist - new_dist
+}
+
+/// First-improvement 2-opt on the open path `path` until no move gains; both
+/// endpoints stay in place. Returns the length saved.
+pub fn two_opt_path(path: &mut [usize], cities: &[City]) -> f64 {
+    let m = path.len();
+    let mut saved = 0.0;
+    let mut improved = m >= 4;
+    while improved {
+        improved = false;
+        for i in 1..m - 2 {
+            for j in i + 1..m - 1 {
+                // j + 1 < m, so the gain never wraps around the path
+                let gain = two_opt_gain(path, cities, i, j);
+                if gain > MIN_GAIN {
+                    reverse_segment(path, i, j);
+                    saved += gain;
+                    improved = true;
+                }
+            }
+        }
+    }
+    saved
+}
+
+/// Gain of every 2-opt move `(i, j)` with `i` in `rows` and `i < j < n`.
+/// Evaluated in parallel over rows; the result is ordered by `(i, j)` like a
+/// sequential double loop.
+pub fn two_opt_gains(tour: &[usize], cities: &[City], rows: &[usize]) -> Vec<(f64, usize, usize)> {
+    let n = tour.len();
+    rows.par_iter()
+        .flat_map_iter(|&i| (i + 1..n).map(move |j| (two_opt_gain(tour, cities, i, j), i, j)))
+        .collect()
+}
+
+/// First improving move found, or the best one
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Improvement {
+    First,
+    Best,
+}
+
+/// A move type usable by the VND / VNS driver
+pub trait Neighborhood: Sync {
+    fn name(&self) -> &'static str;
+
+    /// Find an improving move and apply it; returns the length saved, or `None`
+    /// at a local optimum of this neighbourhood
+    fn improve(&self, tour: &mut [usize], cities: &[City], improvement: Improvement) -> Option<f64>;
+
+    /// Apply one uniformly random move (VNS shaking); returns the length saved,
+    /// usually negative
+    fn shake(&self, tour: &mut [usize], cities: &[City], rng: &mut StdRng) -> f64;
+}
+
+/// Search `rows` in parallel with `row(r)` returning that row's first / best
+/// improving move. First improvement takes the lowest improving row and best
+/// improvement the highest gain (lowest row on ties), so both are deterministic.
+fn search_rows<M, F>(rows: std::ops::Range<usize>, improvement: Improvement, row: F) -> Option<(f64, M)>
+where
+    M: Send,
+    F: Fn(usize) -> Option<(f64, M)> + Sync + Send,
+{
+    match improvement {
+        Improvement::First => rows.into_par_iter().find_map_first(row),
+        Improvement::Best => rows
+            .into_par_iter()
+            .filter_map(row)
+            .reduce_with(|a, b| if b.0 > a.0 { b } else { a }),
+    }
+}
+
+/// Keep `candidate` if it is the first improving move, or better than `best`
+fn consider<M>(best: &mut Option<(f64, M)>, candidate: (f64, M), improvement: Improvement) -> bool {
+    if candidate.0 <= MIN_GAIN {
+        return false;
+    }
+    if best.as_ref().is_none_or(|b| candidate.0 > b.0) {
+        *best = Some(candidate);
+    }
+    improvement == Improvement::First
+}
+
+/// 2-opt: reverse `tour[i..=j]`
+pub struct TwoOpt;
+
+impl Neighborhood for TwoOpt {
+    fn name(&self) -> &'static str {
+        "2-opt"
+    }
+
+    fn improve(&self, tour: &mut [usize], cities: &[City], improvement: Improvement) -> Option<f64> {
+        let n = tour.len();
+        let t = &*tour;
+        let (gain, (i, j)) = search_rows(1..n - 1, improvement, |i| {
+            let mut best = None;
+            for j in i + 1..n {
+                if consider(&mut best, (two_opt_gain(t, cities, i, j), (i, j)), improvement) {
+                    break;
+                }
+            }
+            best
+        })?;
+        reverse_shorter_side(tour, i, j);
+        Some(gain)
+    }
+
+    fn shake(&self, tour: &mut [usize], cities: &[City], rng: &mut StdRng) -> f64 {
+        let n = tour.len();
+        let i = rng.gen_range(1..n - 1);
+        let j = rng.gen_range(i + 1..n);
+        let gain = two_opt_gain(tour, cities, i, j);
+        reverse_shorter_side(tour, i, j);
+        gain
+    }
+}
+
+/// Or-opt: move a segment of 1..=`max_len` cities, optionally reversed, between
+/// two other neighbouring cities
+pub struct OrOpt {
+    pub max_len: usize,
+}
+
+/// Segment start, length, city after which it is reinserted (by position), reversed
+type SegmentMove = (usize, usize, usize, bool);
+
+impl OrOpt {
+    fn gain(tour: &[usize], cities: &[City], (i, len, p, reversed): SegmentMove) -> f64 {
+        let n = tour.len();
+        let dist = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]);
+        let (prev, s1, sl, next) = (tour[i - 1], tour[i], tour[i + len - 1], tour[(i + len) % n]);
+        let (left, right) = (tour[p], tour[(p + 1) % n]);
+        let (first, last) = if reversed { (sl, s1) } else { (s1, sl) };
+        dist(prev, s1) + dist(sl, next) + dist(left, right)
+            - dist(prev, next) - dist(left, first) - dist(last, right)
+    }
+
+    fn apply(tour: &mut [usize], (i, len, p, reversed): SegmentMove) {
+        let start = if p > i {
+            tour[i..=p].rotate_left(len);
+            p + 1 - len
+        } else {
+            tour[p + 1..i + len].rotate_right(len);
+            p + 1
+        };
+        if reversed {
+            tour[start..start + len].reverse();
+        }
+    }
+
+    /// Insertion points outside the segment and not adjacent to its start
+    fn valid(i: usize, len: usize, p: usize) -> bool {
+        p + 1 < i || p >= i + len
+    }
+}
+
+impl Neighborhood for OrOpt {
+    fn name(&self) -> &'static str {
+        "Or-opt"
+    }
+
+    fn improve(&self, tour: &mut [usize], cities: &[City], improvement: Improvement) -> Option<f64> {
+        let n = tour.len();
+        let t = &*tour;
+        let (gain, m) = search_rows(1..n, improvement, |i| {
+            let mut best = None;
+            for len in (1..=self.max_len).take_while(|&len| i + len <= n && len + 2 < n) {
+                for p in (0..n).filter(|&p| Self::valid(i, len, p)) {
+                    for reversed in [false, true] {
+                        let m = (i, len, p, reversed);
+                        if consider(&mut best, (Self::gain(t, cities, m), m), improvement) {
+                            return best;
+                        }
+                    }
+                }
+            }
+            best
+        })?;
+        Self::apply(tour, m);
+        Some(gain)
+    }
+
+    fn shake(&self, tour: &mut [usize], cities: &[City], rng: &mut StdRng) -> f64 {
+        let n = tour.len();
+        let len = rng.gen_range(1..=self.max_len.clamp(1, n - 3));
+        let i = rng.gen_range(1..=n - len);
+        loop {
+            let p = rng.gen_range(0..n);
+            if Self::valid(i, len, p) {
+                let m = (i, len, p, rng.gen_bool(0.5));
+                let gain = Self::gain(tour, cities, m);
+                Self::apply(tour, m);
+                return gain;
+            }
+        }
+    }
+}
+
+/// 3-opt segment exchange: `A B C D -> A C B D` for `B = tour[i..j]`,
+/// `C = tour[j..k]`. Only moves whose first new edge joins the city before `B`
+/// to one of its `k` nearest neighbours are evaluated.
+pub struct ThreeOpt {
+    neighbors: Vec<Vec<usize>>,
+}
+
+impl ThreeOpt {
+    pub fn new(cities: &[City], k: usize) -> Self {
+        Self { neighbors: neighbor_lists(cities, k) }
+    }
+
+    fn gain(tour: &[usize], cities: &[City], i: usize, j: usize, k: usize) -> f64 {
+        let n = tour.len();
+        let dist = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]);
+        let (a, b1, b2, c1, c2, d) = (tour[i - 1], tour[i], tour[j - 1], tour[j], tour[k - 1], tour[k % n]);
+        dist(a, b1) + dist(b2, c1) + dist(c2, d) - dist(a, c1) - dist(c2, b1) - dist(b2, d)
+    }
+}
+
+impl Neighborhood for ThreeOpt {
+    fn name(&self) -> &'static str {
+        "3-opt"
+    }
+
+    fn improve(&self, tour: &mut [usize], cities: &[City], improvement: Improvement) -> Option<f64> {
+        let n = tour.len();
+        let t = &*tour;
+        let pos = position_index(t);
+        let (gain, (i, j, k)) = search_rows(1..n - 1, improvement, |i| {
+            let a = t[i - 1];
+            let d_ab = euclidean_distance(&cities[a], &cities[t[i]]);
+            let mut best = None;
+            for &c1 in &self.neighbors[a] {
+                if euclidean_distance(&cities[a], &cities[c1]) >= d_ab {
+                    break;
+                }
+                let j = pos[c1];
+                if j <= i {
+                    continue;
+                }
+                for k in j + 1..=n {
+                    if consider(&mut best, (Self::gain(t, cities, i, j, k), (i, j, k)), improvement) {
+                        return best;
+                    }
+                }
+            }
+            best
+        })?;
+        tour[i..k].rotate_left(j - i);
+        Some(gain)
+    }
+
+    fn shake(&self, tour: &mut [usize], cities: &[City], rng: &mut StdRng) -> f64 {
+        let n = tour.len();
+        let i = rng.gen_range(1..n - 1);
+        let j = rng.gen_range(i + 1..n);
+        let k = rng.gen_range(j + 1..=n);
+        let gain = Self::gain(tour, cities, i, j, k);
+        tour[i..k].rotate_left(j - i);
+        gain
+    }
+}
+
+/// Swap the cities at positions `i < j`
+pub struct Swap;
+
+impl Swap {
+    fn gain(tour: &[usize], cities: &[City], i: usize, j: usize) -> f64 {
+        let n = tour.len();
+        let dist = |a: usize, b: usize| euclidean_distance(&cities[a], &cities[b]);
+        let (pa, a, na) = (tour[(i + n - 1) % n], tour[i], tour[i + 1]);
+        let (pb, b, nb) = (tour[j - 1], tour[j], tour[(j + 1) % n]);
+        if i == 0 && j == n - 1 {
+            // b a are neighbours across the wrap-around: pb b a na -> pb a b na
+            dist(pb, b) + dist(a, na) - dist(pb, a) - dist(b, na)
+        } else if j == i + 1 {
+            dist(pa, a) + dist(b, nb) - dist(pa, b) - dist(a, nb)
+        } else {
+            dist(pa, a) + dist(a, na) + dist(pb, b) + dist(b, nb)
+                - dist(pa, b) - dist(b, na) - dist(pb, a) - dist(a, nb)
+        }
+    }
+}
+
+impl Neighborhood for Swap {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn improve(&self, tour: &mut [usize], cities: &[City], improvement: Improvement) -> Option<f64> {
+        let n = tour.len();
+        let t = &*tour;
+        let (gain, (i, j)) = search_rows(0..n - 1, improvement, |i| {
+            let mut best = None;
+            // (0, n - 1) are neighbours across the wrap-around; skipped
+            for j in (i + 1..n).filter(|&j| i > 0 || j < n - 1) {
+                if consider(&mut best, (Self::gain(t, cities, i, j), (i, j)), improvement) {
+                    break;
+                }
+            }
+            best
+        })?;
+        tour.swap(i, j);
+        Some(gain)
+    }
+
+    fn shake(&self, tour: &mut [usize], cities: &[City], rng: &mut StdRng) -> f64 {
+        let n = tour.len();
+        let i = rng.gen_range(0..n - 1);
+        let j = rng.gen_range(i + 1..n);
+        let gain = Self::gain(tour, cities, i, j);
+        tour.swap(i, j);
+        gain
+    }
+}
+
+/// 2-opt, Or-opt (segments up to 3), 3-opt (10 neighbours) and swap, in that order
+#[allow(dead_code)]
+pub fn default_neighborhoods(cities: &[City]) -> Vec<Box<dyn Neighborhood>> {
+    vec![
+        Box::new(TwoOpt),
+        Box::new(OrOpt { max_len: 3 }),
+        Box::new(ThreeOpt::new(cities, 10)),
+        Box::new(Swap),
+    ]
+}
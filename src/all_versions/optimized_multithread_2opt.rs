@@ -4,6 +4,9 @@ use std::cmp::Ordering;
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use crate::all_versions::batch_moves::{apply_batch, select_independent};
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::two_opt_gain;
 use crate::all_versions::utils::*;
 
 // this version to optimize the original code is try to avoid calculating the total distance
@@ -16,10 +19,6 @@ use crate::all_versions::utils::*;
 // which means not exist in the previous selected range
 
 // TODO: if there is a way to parallize the calculation of the total distance among the current shuffle
-
-fn swap2edges(tour: &mut [usize], i: usize, j: usize) {
-    tour[i..=j].reverse();
-}
 #[allow(dead_code)]
 pub fn multi_2opt_optimized1(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
     let n = tour.len();
@@ -40,7 +39,7 @@ pub fn multi_2opt_optimized1(tour: &[usize], cities: &[City]) -> (Vec<usize>, f6
             tour.shuffle(&mut rng); // keep these part the same
 
             let mut route = tour.clone(); // original shuffle for current thread
-            let mut dis = compute_total_distance(&route, &cities); // ⬅️ updated here
+            let mut dis = CostTracker::new(&route, cities);
 
             while true {
                 let sampled = possibilities.choose_multiple(&mut rng, sample_size)
@@ -52,41 +51,22 @@ pub fn multi_2opt_optimized1(tour: &[usize], cities: &[City]) -> (Vec<usize>, f6
                 for &(i, j) in &sampled {
                     if i == 0 || j + 1 >= n { continue; }
 
-                    let non_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[i]])
-                        + euclidean_distance(&cities[route[j]], &cities[route[(j + 1) % n]]);
-                    let after_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[j]])
-                        + euclidean_distance(&cities[route[i]], &cities[route[(j + 1) % n]]);
+                    let gain = two_opt_gain(&route, cities, i, j);
 
-                    if non_swap - after_swap > 10.0 {
-                        can_modify.push((i, j, non_swap - after_swap));
+                    if gain > 10.0 {
+                        can_modify.push((gain, i, j));
                     }
                 }
 
-                can_modify.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-
-                let mut used = vec![false; n];
-                let mut selected = vec![];
-
-                for &(i, j, _) in &can_modify {
-                    if used[i - 1] || used[i] || used[j] || used[(j + 1) % n] {
-                        continue;
-                    }
-                    selected.push((i, j));
-                    used[i - 1] = true;
-                    used[i] = true;
-                    used[j] = true;
-                    used[(j + 1) % n] = true;
-                }
-
-                for &(i, j) in &selected {
-                    swap2edges(&mut route, i, j);
-                }
-
-                let new_dis = compute_total_distance(&route, &cities); // ⬅️ updated here
-                if dis - new_dis < 1.0 { break; }
-                dis = new_dis;
+                // apply the best independent swaps (nested ones too, crossing ones never)
+                let selected = select_independent(n, &mut can_modify, n);
+                let saved = apply_batch(&mut route, cities, &selected);
+                dis.saved(saved);
+                dis.checkpoint(&route, cities);
+                if saved < 1.0 { break; }
             }
 
+            let dis = dis.resync(&route, cities);
             (route, dis)
         })
         .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
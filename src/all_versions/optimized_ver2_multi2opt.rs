@@ -1,9 +1,14 @@
 /// Multithreaded Version 3️⃣
 use rayon::prelude::*;
+use std::time::Instant;
 use std::cmp::Ordering;
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use crate::all_versions::construction::nearest_neighbor_tour;
+use crate::all_versions::batch_moves::{apply_batch, select_independent};
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::two_opt_gain;
 use crate::all_versions::utils::*;
 
 // after doing some experiments on the optimize version1 of multithread 2opt
@@ -21,34 +26,20 @@ use crate::all_versions::utils::*;
 
 // what about construct some route that may already have some local optimization
 
-fn swap2edges(tour: &mut [usize], i: usize, j: usize) {
-    tour[i..=j].reverse();
+#[allow(dead_code)]
+pub fn multi_2opt_optimized2_V2(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+    run(tour, cities, false, None)
 }
 
-fn get_initial_route(start_point: usize, cities: &[City]) -> Vec<usize> {
-    // a randomized start_position to start a greedy algotrithm to get the initial route
-    let n = cities.len();
-    let mut initial_route = vec![];
-    let mut vis = vec![false; n]; // to mark whether a city is visited
-    initial_route.push(start_point);
-    vis[start_point] = true;
-    let mut prev = start_point;
-
-    for _ in 1..n {
-        let nxt = (0..n).filter(|&i| !vis[i]).min_by(|&a, &b| {
-            euclidean_distance(&cities[prev], &cities[a])
-                    .partial_cmp(&euclidean_distance(&cities[prev], &cities[b]))
-                    .unwrap()
-        }).unwrap(); // the idea is that get all the distance from unvisited cities to the previous added city
-        // and choose the nearest city to push back to the vector 
-        initial_route.push(nxt);
-        vis[nxt] = true; // this city
-        prev = nxt; // like the linked list, move the pointer to the next position as the previous city
-    }
-    initial_route
-}
+/// Warm-started `multi_2opt_optimized2_V2` for the portfolio: the first of the 32 starts is
+/// `tour` itself, and every start stops after the first round ending past `deadline`, and
+/// starts not begun by then are skipped
 #[allow(dead_code)]
-pub fn multi_2opt_optimized2_V2(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+pub fn multi_2opt_optimized2_v2_warm(tour: &[usize], cities: &[City], deadline: Option<Instant>) -> (Vec<usize>, f64) {
+    run(tour, cities, true, deadline)
+}
+
+fn run(tour: &[usize], cities: &[City], warm: bool, deadline: Option<Instant>) -> (Vec<usize>, f64) {
     let n = tour.len();
     let possibilities: Vec<(usize, usize)> = (1..n - 1)
             .into_par_iter()
@@ -60,23 +51,30 @@ pub fn multi_2opt_optimized2_V2(tour: &[usize], cities: &[City]) -> (Vec<usize>,
     // the number of threads can be changed later, the original number of threads is 16
     let better_res = (0..32)
     .into_par_iter()
-    .map(|_| {
-        
+    .filter_map(|start| {
+        // starts that have not begun by the deadline are skipped; the warm one always runs
+        if deadline.is_some_and(|d| Instant::now() >= d) && !(warm && start == 0) {
+            return None;
+        }
+
         // we may construct a better initial route
         // and to ensure that all the threads are optimized themselves without
         // doing redundent works, we may also do some randomize work on the start position
         let mut rng = thread_rng();
-        let mut initial_route = if rng.gen_bool(0.5) {
+        let mut initial_route = if warm && start == 0 {
+            tour.to_vec()
+        } else if rng.gen_bool(0.5) {
             let mut tmp = (0..n).collect::<Vec<_>>();
             tmp.shuffle(&mut rng);
             tmp
         } else {
-            get_initial_route(rng.gen_range(0..n), &cities)
+            nearest_neighbor_tour(rng.gen_range(0..n), &cities)
         };
         
         let mut route = initial_route.clone(); // original shuffle for current thread
-        let mut dis = compute_total_distance(&route, &cities);
+        let mut dis = CostTracker::new(&route, cities);
         while true { // continue using the repeated sampled based method
+            if deadline.is_some_and(|d| Instant::now() >= d) { break; }
             let sampled = possibilities.choose_multiple(&mut rng, sample_size)
                             .cloned()
                             .collect::<Vec<_>>();
@@ -91,42 +89,23 @@ pub fn multi_2opt_optimized2_V2(tour: &[usize], cities: &[City]) -> (Vec<usize>,
                 // we can try to compare the original dis of the 2 edge and after swap the 2 edges. 
                 // but there is an issue, if we modify the original route immediately if the new route cost 
                 // less than original version, then the next operation is not on the original version
-                let non_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[i]])
-                        + euclidean_distance(&cities[route[j]], &cities[route[(j + 1) % n]]);
-                let after_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[j]])
-                        + euclidean_distance(&cities[route[i]], &cities[route[(j + 1) % n]]);
-
-                if non_swap - after_swap > 10.0 {
-                    can_modify.push((i, j, non_swap - after_swap));
-                }
-            }
+                let gain = two_opt_gain(&route, cities, i, j);
 
-            can_modify.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
-
-            let mut used = vec![false; n];
-            let mut selected = vec![];
-
-            for &(i, j, _) in &can_modify {
-                if used[i - 1] || used[i] || used[j] || used[(j + 1) % n] {
-                    continue;
+                if gain > 10.0 {
+                    can_modify.push((gain, i, j));
                 }
-                selected.push((i, j));
-                used[i - 1] = true;
-                used[i] = true;
-                used[j] = true; 
-                used[(j + 1) % n] = true;
-            }
-
-            for &(i, j) in &selected {
-                swap2edges(&mut route, i, j);
             }
 
-            let new_dis = compute_total_distance(&route, &cities);
-            if dis - new_dis < 1.0 { break; }
-            dis = new_dis;
+            // apply the best independent swaps (nested ones too, crossing ones never)
+            let selected = select_independent(n, &mut can_modify, n);
+            let saved = apply_batch(&mut route, cities, &selected);
+            dis.saved(saved);
+            dis.checkpoint(&route, cities);
+            if saved < 1.0 { break; }
         }
 
-        (route, dis)
+        let dis = dis.resync(&route, cities);
+        Some((route, dis))
     })
     .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
     .unwrap();
@@ -1,9 +1,94 @@
 /// GA Version 3️⃣ Fully Parallel GA with Delayed 2-Opt
+/// Supports seeded, reproducible runs with periodic checkpoints (see `ga_checkpoint`)
+/// and a memetic mode that locally optimises every child (see `local_search`).
+/// Mutation rate and operator choice can be adapted online (see `ga_adaptive`).
 use rayon::prelude::*;
-use rand::seq::SliceRandom;
 use rand::{Rng, thread_rng};
+use std::io;
+use std::path::PathBuf;
+use std::time::Instant;
+use crate::all_versions::candidate_set::Candidates;
+use crate::all_versions::construction::seeded_population;
+use crate::all_versions::ga_adaptive::*;
+use crate::all_versions::ga_checkpoint::*;
+use crate::all_versions::ga_log::*;
+use crate::all_versions::local_search::*;
 use crate::all_versions::utils::*;
 
+/// Parameters of the parallel GA. Field meanings match `run_ga_parallel`'s arguments.
+#[derive(Clone, Debug)]
+pub struct GaParams {
+    pub population_size: usize,
+    pub generations: usize,
+    pub base_mutation_rate: f64,
+    pub elitism_k: usize,
+    pub refine_start: usize,
+    pub refine_every: usize,
+    pub top_n: usize,
+    /// Which `top_n` individuals the delayed 2-opt refines
+    pub refine_policy: RefinePolicy,
+    pub early_stop_patience: usize,
+    pub seed: u64,
+    /// Fraction of the initial population built by construction heuristics
+    pub seed_fraction: f64,
+    /// When set, every child gets a bounded local search right after crossover/mutation
+    pub memetic: Option<MemeticConfig>,
+    /// Per-generation CSV log (see `ga_log`)
+    pub log_path: Option<PathBuf>,
+    /// How the mutation rate evolves over the run
+    pub mutation_control: MutationControl,
+    /// When set, each child picks its crossover / mutation operator by recent
+    /// success; otherwise OX + swap mutation as before
+    pub operator_credit: Option<CreditConfig>,
+    /// Stop after the first generation that ends past this instant; the 2-opt
+    /// refinements stop after their first pass ending past it
+    pub deadline: Option<Instant>,
+}
+
+/// Per-child local search settings for the memetic mode
+#[derive(Clone, Debug)]
+pub struct MemeticConfig {
+    pub kind: LocalSearchKind,
+    /// Max city activations per child (0 = run each child to a local optimum);
+    /// trades generations against local search depth
+    pub budget: usize,
+    /// Candidate lists of the local search
+    pub candidates: Candidates,
+}
+
+impl Default for MemeticConfig {
+    fn default() -> Self {
+        Self {
+            kind: LocalSearchKind::Both,
+            budget: 0,
+            candidates: Candidates::Nearest(8),
+        }
+    }
+}
+
+impl Default for GaParams {
+    fn default() -> Self {
+        Self {
+            population_size: 100,
+            generations: 300,
+            base_mutation_rate: 0.1,
+            elitism_k: 5,
+            refine_start: 100,
+            refine_every: 100,
+            top_n: 10,
+            refine_policy: RefinePolicy::BestN,
+            early_stop_patience: 100,
+            seed: 121,
+            seed_fraction: 0.0,
+            memetic: None,
+            log_path: None,
+            mutation_control: MutationControl::LinearDecay,
+            operator_credit: None,
+            deadline: None,
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn run_ga_parallel(
     cities: &[City],
@@ -15,61 +100,173 @@ pub fn run_ga_parallel(
     refine_every: usize,
     top_n: usize,
 ) -> Individual {
-    let city_count = cities.len();
-    let mut rng = thread_rng();
-
-    let early_stop_patience = 100;
-    let mut best_fitness_so_far = f64::MIN;
-    let mut generations_without_improvement = 0;
-
-    let mut population: Vec<Individual> = (0..population_size)
-        .map(|_| {
-            let mut tour: Vec<usize> = (0..city_count).collect();
-            tour.shuffle(&mut rng);
-            Individual::new(tour, cities)
-        })
-        .collect();
-
-    for gen in 0..generations {
-        let mutation_rate = base_mutation_rate * (1.0 - gen as f64 / generations as f64);
-
-        // Parallel child generation
-        let new_population: Vec<Individual> = (0..population_size)
+    let params = GaParams {
+        population_size,
+        generations,
+        base_mutation_rate,
+        elitism_k,
+        refine_start,
+        refine_every,
+        top_n,
+        refine_policy: RefinePolicy::BestN,
+        early_stop_patience: 100,
+        seed: thread_rng().gen(),
+        seed_fraction: 0.0,
+        memetic: None,
+        log_path: None,
+        mutation_control: MutationControl::LinearDecay,
+        operator_credit: None,
+        deadline: None,
+    };
+    run_ga_parallel_seeded(cities, &params, None)
+        .expect("no checkpoint configured, so no I/O can fail")
+}
+
+/// Seeded parallel GA. With a `checkpoint`, the state is written to disk every
+/// `checkpoint.every` generations so the run can be continued with `resume_ga_parallel`.
+#[allow(dead_code)]
+pub fn run_ga_parallel_seeded(
+    cities: &[City],
+    params: &GaParams,
+    checkpoint: Option<&CheckpointConfig>,
+) -> io::Result<Individual> {
+    let population = seeded_population(cities, params.population_size, params.seed_fraction, params.seed);
+    evolve(cities, params, initial_state(params, population), checkpoint)
+}
+
+/// Seeded parallel GA whose initial population contains `warm_start` (e.g. the
+/// incumbent of a portfolio run); with elitism it survives until beaten.
+#[allow(dead_code)]
+pub fn run_ga_parallel_warm(cities: &[City], params: &GaParams, warm_start: &[usize]) -> Individual {
+    let mut population = seeded_population(cities, params.population_size, params.seed_fraction, params.seed);
+    if let Some(slot) = population.first_mut() {
+        *slot = Individual::new(warm_start.to_vec(), cities);
+    }
+    evolve(cities, params, initial_state(params, population), None)
+        .expect("no checkpoint configured, so no I/O can fail")
+}
+
+fn initial_state(params: &GaParams, population: Vec<Individual>) -> GaState {
+    GaState {
+        seed: params.seed,
+        generation: 0,
+        population,
+        best_so_far: None,
+        generations_without_improvement: 0,
+        adaptive: AdaptiveState::new(params.base_mutation_rate),
+    }
+}
+
+/// Continue a run from the checkpoint at `checkpoint.path`. Given the same `params`,
+/// the result is identical to the uninterrupted seeded run.
+#[allow(dead_code)]
+pub fn resume_ga_parallel(
+    cities: &[City],
+    params: &GaParams,
+    checkpoint: &CheckpointConfig,
+) -> io::Result<Individual> {
+    let state = load_checkpoint(&checkpoint.path, cities)?;
+    println!("Resuming from generation {} ({})", state.generation, checkpoint.path.display());
+    evolve(cities, params, state, Some(checkpoint))
+}
+
+fn evolve(
+    cities: &[City],
+    params: &GaParams,
+    mut state: GaState,
+    checkpoint: Option<&CheckpointConfig>,
+) -> io::Result<Individual> {
+    let generations = params.generations;
+    let neighbors = params
+        .memetic
+        .as_ref()
+        .map(|m| m.candidates.build(cities));
+    let mut log = GenerationLog::open(params.log_path.as_deref(), state.generation > 0)?;
+
+    for gen in state.generation..generations {
+        let mut decision = AdaptiveDecision::default();
+        let mutation_rate = match params.mutation_control {
+            MutationControl::LinearDecay => {
+                params.base_mutation_rate * (1.0 - gen as f64 / generations as f64)
+            }
+            MutationControl::OneFifth { .. } => state.adaptive.mutation_rate,
+            MutationControl::Diversity { min_rate, max_rate } => {
+                let diversity = population_diversity(&state.population);
+                decision.diversity = Some(diversity);
+                max_rate - (max_rate - min_rate) * diversity.clamp(0.0, 1.0)
+            }
+        };
+        if let Some(credit) = &params.operator_credit {
+            decision.mutation_probs = AdaptiveState::probabilities(&state.adaptive.mutation_quality, credit);
+            decision.crossover_probs = AdaptiveState::probabilities(&state.adaptive.crossover_quality, credit);
+        }
+        let population = &state.population;
+        let adaptive_ops = params.operator_credit.is_some();
+
+        // Parallel child generation, one RNG stream per child slot
+        let (new_population, outcomes): (Vec<Individual>, Vec<ChildOutcome>) = (0..params.population_size)
             .into_par_iter()
-            .map(|_| {
-                let parent1 = tournament_selection(&population, 5);
-                let parent2 = tournament_selection(&population, 5);
-                let mut child_tour = order_crossover(&parent1.tour, &parent2.tour);
+            .map(|slot| {
+                let mut rng = stream_rng(state.seed, gen as u64, slot as u64);
+                let parent1 = tournament_selection_with_rng(population, 5, &mut rng);
+                let parent2 = tournament_selection_with_rng(population, 5, &mut rng);
+                let crossover = if adaptive_ops { AdaptiveState::pick(&decision.crossover_probs, &mut rng) } else { 0 };
+                let mut child_tour = CROSSOVER_OPS[crossover].apply(&parent1.tour, &parent2.tour, &mut rng);
+
+                let mut mutation = None;
+                if rng.gen::<f64>() < mutation_rate {
+                    let m = if adaptive_ops { AdaptiveState::pick(&decision.mutation_probs, &mut rng) } else { 0 };
+                    MUTATION_OPS[m].apply(&mut child_tour, &mut rng);
+                    mutation = Some(m);
+                }
 
-                if rand::random::<f64>() < mutation_rate {
-                    swap_mutation(&mut child_tour);
+                if let (Some(m), Some(nb)) = (&params.memetic, &neighbors) {
+                    local_search(&mut child_tour, cities, nb, m.kind, m.budget);
                 }
 
-                Individual::new(child_tour, cities)
+                let child = Individual::new(child_tour, cities);
+                let parent_best = parent1.distance().min(parent2.distance());
+                let reward = ((parent_best - child.distance()) / parent_best).max(0.0);
+                (child, ChildOutcome { crossover, mutation, reward })
             })
-            .collect();
+            .unzip();
+
+        state.adaptive.update(&outcomes, &params.mutation_control, params.operator_credit.as_ref(), &mut decision);
 
         // Elitism (sequential)
-        population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
+        state.population.sort_by(|a, b| b.fitness.partial_cmp(&a.fitness).unwrap());
         let mut new_population = new_population;
-        for (i, elite) in population.iter().take(elitism_k).cloned().enumerate() {
+        for (i, elite) in state.population.iter().take(params.elitism_k).cloned().enumerate() {
             new_population[i] = elite;
         }
 
-        population = new_population;
+        state.population = new_population;
 
-        // Parallel 2-opt refinement on top N individuals
-        if gen >= refine_start && gen % refine_every == 0 {
-            population
+        // Parallel 2-opt refinement on the N individuals chosen by the policy
+        let mut refined_idx = Vec::new();
+        if gen >= params.refine_start && gen % params.refine_every == 0 {
+            let mut rng = stream_rng(state.seed, gen as u64, u64::MAX);
+            refined_idx = select_for_refinement(&state.population, params.top_n, params.refine_policy, &mut rng);
+            let mut chosen = vec![false; state.population.len()];
+            for &i in &refined_idx {
+                chosen[i] = true;
+            }
+            state.population
                 .par_iter_mut()
-                .take(top_n)
-                .for_each(|ind| {
-                    let refined = two_opt(&ind.tour, cities);
+                .zip(chosen.par_iter())
+                .filter(|(_, &c)| c)
+                .for_each(|(ind, _)| {
+                    let refined = two_opt_until(&ind.tour, cities, params.deadline);
                     *ind = Individual::new(refined, cities);
                 });
+            println!("Generation {}: refined {:?} ({:?})", gen, refined_idx, params.refine_policy);
         }
 
-        let best = population
+        let mut stats = GenerationStats::new(gen, &state.population, mutation_rate, refined_idx);
+        stats.adaptive = decision;
+        log.record(&stats)?;
+
+        let best = state.population
             .iter()
             .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
             .unwrap();
@@ -83,27 +280,40 @@ pub fn run_ga_parallel(
             );
         }
 
-        if best.fitness > best_fitness_so_far {
-            best_fitness_so_far = best.fitness;
-            generations_without_improvement = 0;
+        if best.fitness > state.best_fitness_so_far() {
+            state.best_so_far = Some(best.clone());
+            state.generations_without_improvement = 0;
         } else {
-            generations_without_improvement += 1;
+            state.generations_without_improvement += 1;
         }
 
-        if generations_without_improvement >= early_stop_patience {
+        state.generation = gen + 1;
+        if let Some(cp) = checkpoint {
+            if cp.every > 0 && state.generation.is_multiple_of(cp.every) {
+                save_checkpoint(&cp.path, &state, cities.len())?;
+            }
+        }
+
+        if state.generations_without_improvement >= params.early_stop_patience {
             println!(
                 "Early stopping at generation {} (no improvement in {} generations)",
-                gen, early_stop_patience
+                gen, params.early_stop_patience
             );
             break;
         }
+        if params.deadline.is_some_and(|d| Instant::now() >= d) {
+            println!("Deadline reached at generation {}", gen);
+            break;
+        }
     }
 
-    let best = population
+    log.flush()?;
+
+    let best = state.population
         .into_iter()
         .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
         .unwrap();
 
-    let refined = two_opt(&best.tour, cities);
-    Individual::new(refined, cities)
+    let refined = two_opt_until(&best.tour, cities, params.deadline);
+    Ok(Individual::new(refined, cities))
 }
@@ -1,3 +1,4 @@
+use crate::all_versions::neighborhoods::{reverse_shorter_side, two_opt_gain};
 use crate::all_versions::utils::*;
 use rayon::prelude::*;
 use std::cmp::Ordering;
@@ -28,17 +29,7 @@ pub fn par_prototype(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
         if let Some((i_best, j_best)) = candidates
             .par_iter()
             .filter_map(|&(i, j)| {
-                let a = new_tour[i - 1];
-                let b = new_tour[i];
-                let c = new_tour[j];
-                let d = new_tour[(j + 1) % n];
-
-                let current_dist = euclidean_distance(&cities[a], &cities[b])
-                    + euclidean_distance(&cities[c], &cities[d]);
-                let new_dist = euclidean_distance(&cities[a], &cities[c])
-                    + euclidean_distance(&cities[b], &cities[d]);
-
-                let delta = current_dist - new_dist;
+                let delta = two_opt_gain(&new_tour, cities, i, j);
 
                 if delta > DELTA_THRESHOLD {
                     Some((delta, i, j))
@@ -49,7 +40,7 @@ pub fn par_prototype(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
             .max_by(|(da, _, _), (db, _, _)| da.partial_cmp(db).unwrap_or(Ordering::Equal))
             .map(|(_, i, j)| (i, j))
         {
-            new_tour[i_best..=j_best].reverse();
+            reverse_shorter_side(&mut new_tour, i_best, j_best);
             improved = true;
         }
     }
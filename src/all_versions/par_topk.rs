@@ -1,13 +1,15 @@
+use crate::all_versions::batch_moves::batch_two_opt_round;
+use crate::all_versions::cost_tracker::CostTracker;
 use crate::all_versions::utils::*;
-use rayon::prelude::*;
-use std::cmp::Ordering;
 
 /// Basic 2-opt Version 2️⃣
-/// Parallel Top-K 2-Opt: Selects top `k` non-overlapping improving swaps each iteration.
+/// Parallel Top-K 2-Opt: Applies the top `k` independent improving swaps each
+/// iteration (`batch_moves`).
 #[allow(dead_code)]
 pub fn par_topk(tour: &[usize], cities: &[City], k: usize) -> (Vec<usize>, f64) {
     let mut improved = true;
     let mut new_tour = tour.to_vec();
+    let mut cost = CostTracker::new(&new_tour, cities);
     let mut loop_count = 0;
     const DELTA_THRESHOLD: f64 = 1e-6;
 
@@ -18,69 +20,17 @@ pub fn par_topk(tour: &[usize], cities: &[City], k: usize) -> (Vec<usize>, f64)
             break;
         }
 
-        improved = false;
         let n = new_tour.len();
 
-        // Step 1: Generate all (i, j) pairs in parallel and compute deltas
-        let mut candidates: Vec<(f64, usize, usize)> = (1..n - 1)
-            .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
-            .collect::<Vec<_>>()
-            .par_iter()
-            .filter_map(|&(i, j)| {
-                let a = new_tour[i - 1];
-                let b = new_tour[i];
-                let c = new_tour[j];
-                let d = new_tour[(j + 1) % n];
-
-                let current_dist = euclidean_distance(&cities[a], &cities[b])
-                    + euclidean_distance(&cities[c], &cities[d]);
-                let new_dist = euclidean_distance(&cities[a], &cities[c])
-                    + euclidean_distance(&cities[b], &cities[d]);
-
-                let delta = current_dist - new_dist;
-                if delta > DELTA_THRESHOLD {
-                    Some((delta, i, j))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        if candidates.is_empty() {
-            break;
-        }
-
-        // Step 2: Sort by delta descending
-        candidates.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal));
-
-        // Step 3: Select top-k non-overlapping swaps
-        let mut applied_flags = vec![false; n];
-        let mut to_apply = vec![];
-        let mut count = 0;
-
-        for &(_, i, j) in &candidates {
-            if count >= k {
-                break;
-            }
-
-            if applied_flags[i..=j].iter().any(|&used| used) {
-                continue;
-            }
-
-            to_apply.push((i, j));
-            for idx in i..=j {
-                applied_flags[idx] = true;
-            }
-            count += 1;
-        }
-
-        // Step 4: Apply the swaps
-        for (i, j) in to_apply {
-            new_tour[i..=j].reverse();
-            improved = true;
-        }
+        // Evaluate all deltas in parallel, apply the top-k independent swaps
+        let rows: Vec<usize> = (1..n - 1).collect();
+        let (applied, saved) = batch_two_opt_round(&mut new_tour, cities, &rows, DELTA_THRESHOLD, k);
+        cost.saved(saved);
+        cost.checkpoint(&new_tour, cities);
+        improved = applied > 0;
     }
 
-    let final_cost = compute_total_distance(&new_tour, cities);
+    let final_cost = cost.resync(&new_tour, cities);
     (new_tour, final_cost)
 }
+
@@ -0,0 +1,176 @@
+/// Portfolio solver: race several solvers in parallel under one time budget
+///
+/// Every member runs on its own OS thread with a private rayon pool of
+/// `threads` workers, in rounds: it takes the current incumbent (the best tour
+/// any member has published) as a warm start, runs its solver once and
+/// publishes the result if it beats the incumbent. A round that fails to
+/// improve is followed by one that starts from a double-bridge kick of the
+/// incumbent, unless another member has published in the meantime.
+///
+/// Solvers receive the remaining budget and stop at the deadline: SA and ILS use
+/// it as their time limit, the 2-opt variants check it after every pass and the
+/// GA after every generation and after every pass of its 2-opt refinements. A
+/// round therefore overruns the deadline by at most one pass or generation, which
+/// grows with n (an O(n²) sampling round for `mult4`); its result still counts,
+/// and no member starts a new round after the deadline.
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::all_versions::iterated_local_search::*;
+use crate::all_versions::optimized_ver2_multi2opt::*;
+use crate::all_versions::par_ga::*;
+use crate::all_versions::par_topkplus::*;
+use crate::all_versions::random_insert_ver3_multi2opt::*;
+use crate::all_versions::simulated_annealing::*;
+use crate::all_versions::two_opt_seq::*;
+use crate::all_versions::utils::*;
+
+/// One solver round: warm start, cities, remaining budget -> (tour, length)
+pub type SolveFn = dyn Fn(&[usize], &[City], Duration) -> (Vec<usize>, f64) + Send + Sync;
+
+pub struct PortfolioMember {
+    pub name: String,
+    /// Size of the member's private rayon pool
+    pub threads: usize,
+    pub solve: Box<SolveFn>,
+}
+
+impl PortfolioMember {
+    #[allow(dead_code)]
+    pub fn new(
+        name: &str,
+        threads: usize,
+        solve: impl Fn(&[usize], &[City], Duration) -> (Vec<usize>, f64) + Send + Sync + 'static,
+    ) -> Self {
+        Self { name: name.to_string(), threads: threads.max(1), solve: Box::new(solve) }
+    }
+
+    /// Members named after the runner versions: `seq`, `topkplus`, `mult3`,
+    /// `mult4`, `ga3`, `sa`, `ils`. `mult3` / `mult4` start one of their 32
+    /// descents from the warm start and the others from their own start tours.
+    #[allow(dead_code)]
+    pub fn preset(version: &str, threads: usize) -> Option<Self> {
+        let member = match version {
+            "seq" => Self::new(version, threads, |t, c, remaining| two_opt_seq_until(t, c, Some(Instant::now() + remaining))),
+            "topkplus" => Self::new(version, threads, |t, c, remaining| {
+                par_topkplus_until(t, c, 10, 1e-5, Some(Instant::now() + remaining))
+            }),
+            "mult3" => Self::new(version, threads, |t, c, remaining| {
+                multi_2opt_optimized2_v2_warm(t, c, Some(Instant::now() + remaining))
+            }),
+            "mult4" => Self::new(version, threads, |t, c, remaining| {
+                multi_2opt_random_insert_warm(t, c, Some(Instant::now() + remaining))
+            }),
+            "ga3" => Self::new(version, threads, |t, c, remaining| {
+                let params = GaParams {
+                    generations: 100,
+                    early_stop_patience: 30,
+                    deadline: Some(Instant::now() + remaining),
+                    ..GaParams::default()
+                };
+                let best = run_ga_parallel_warm(c, &params, t);
+                let cost = best.distance();
+                (best.tour, cost)
+            }),
+            "sa" => Self::new(version, threads, |t, c, remaining| {
+                let mut config = SaConfig::default();
+                config.stop.time_limit = Some(remaining);
+                simulated_annealing(t, c, &config)
+            }),
+            "ils" => Self::new(version, threads, |t, c, remaining| {
+                let mut config = IlsConfig::default();
+                config.stop.time_limit = Some(remaining);
+                iterated_local_search(t, c, &config)
+            }),
+            _ => return None,
+        };
+        Some(member)
+    }
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub struct PortfolioResult {
+    pub tour: Vec<usize>,
+    pub cost: f64,
+    /// Name of the member that produced `tour` ("initial" if none improved it)
+    pub member: String,
+    /// (member, rounds completed, improvements published)
+    pub rounds: Vec<(String, usize, usize)>,
+}
+
+/// The shared best tour; `version` counts publications
+struct Incumbent {
+    tour: Vec<usize>,
+    cost: f64,
+    member: Option<usize>,
+    version: usize,
+}
+
+/// Race `members` from `tour` for `budget`
+#[allow(dead_code)]
+pub fn portfolio(tour: &[usize], cities: &[City], members: &[PortfolioMember], budget: Duration, seed: u64) -> PortfolioResult {
+    let start = Instant::now();
+    let deadline = start + budget;
+    let incumbent = Mutex::new(Incumbent {
+        tour: tour.to_vec(),
+        cost: compute_total_distance(tour, cities),
+        member: None,
+        version: 0,
+    });
+
+    let rounds: Vec<(String, usize, usize)> = thread::scope(|scope| {
+        let handles: Vec<_> = members
+            .iter()
+            .enumerate()
+            .map(|(m, member)| {
+                let incumbent = &incumbent;
+                scope.spawn(move || {
+                    let pool = rayon::ThreadPoolBuilder::new()
+                        .num_threads(member.threads)
+                        .build()
+                        .expect("failed to build member thread pool");
+                    let mut rng = stream_rng(seed, m as u64, 0);
+                    let (mut rounds, mut published) = (0, 0);
+                    let mut stale_version = None;
+
+                    loop {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            break;
+                        }
+                        let (mut warm, warm_cost, version) = {
+                            let inc = incumbent.lock().unwrap();
+                            (inc.tour.clone(), inc.cost, inc.version)
+                        };
+                        if stale_version == Some(version) {
+                            double_bridge_with_rng(&mut warm, &mut rng);
+                        }
+
+                        let (found, cost) = pool.install(|| (member.solve)(&warm, cities, deadline - now));
+                        rounds += 1;
+
+                        let mut inc = incumbent.lock().unwrap();
+                        if cost < inc.cost - 1e-9 {
+                            inc.tour = found;
+                            inc.cost = cost;
+                            inc.member = Some(m);
+                            inc.version += 1;
+                            published += 1;
+                            stale_version = None;
+                            println!("🏁 {:>8.2?}: `{}` published {:.2}", start.elapsed(), member.name, cost);
+                        } else if cost >= warm_cost - 1e-9 {
+                            stale_version = Some(inc.version);
+                        }
+                    }
+                    (member.name.clone(), rounds, published)
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().expect("portfolio member panicked")).collect()
+    });
+
+    let inc = incumbent.into_inner().unwrap();
+    let member = inc.member.map_or_else(|| "initial".to_string(), |m| members[m].name.clone());
+    PortfolioResult { tour: inc.tour, cost: inc.cost, member, rounds }
+}
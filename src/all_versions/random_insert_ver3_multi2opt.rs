@@ -1,69 +1,31 @@
 /// Multithreaded Version 4️⃣
 use rayon::prelude::*;
+use std::time::Instant;
 use std::cmp::Ordering;
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 use rand::Rng;
+use crate::all_versions::construction::insertion_tour;
+use crate::all_versions::batch_moves::{apply_batch, select_independent};
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::two_opt_gain;
 use crate::all_versions::utils::*;
 
 // what about construct some route that may already have some local optimization
-fn get_tot_dis(tour: &[usize], cities: &[City]) -> f64 {
-    let dist_sum: f64 = tour
-        .windows(2)
-        .map(|w| euclidean_distance(&cities[w[0]], &cities[w[1]]))
-        .sum();
-
-    dist_sum + euclidean_distance(&cities[tour[tour.len() - 1]], &cities[tour[0]])
+#[allow(dead_code)]
+pub fn multi_2opt_random_insert(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+    run(tour, cities, false, None)
 }
 
-fn swap2edges(tour: &mut [usize], i: usize, j: usize) {
-    tour[i..=j].reverse();
+/// Warm-started `multi_2opt_random_insert` for the portfolio: the first of the 32 starts is
+/// `tour` itself, and every start stops after the first round ending past `deadline`, and
+/// starts not begun by then are skipped
+#[allow(dead_code)]
+pub fn multi_2opt_random_insert_warm(tour: &[usize], cities: &[City], deadline: Option<Instant>) -> (Vec<usize>, f64) {
+    run(tour, cities, true, deadline)
 }
 
-fn get_initial_route(remain: Vec<usize>, cities: &[City]) -> Vec<usize> {
-    // a randomized start_position to start a greedy algotrithm to get the initial route
-    let n = cities.len();
-
-    // start with first several points
-    // the initial set can be adjust to smaller set, but the idea is that make sure
-    // there is less possibility that some of the threads will get the same initial set
-    // then they will do some redundent job
-    let seed_size = (n / 100).max(2); // at least 2 cities to start with
-    let mut initial_route: Vec<usize> = remain[..seed_size].to_vec();
-    
-    for &city in &remain[n / 100..] {
-        // find the best position to insert the city
-        let mut best_pos = 0;
-        let mut lowest_cost = f64::INFINITY;
-
-        for i in 0..(initial_route.len() + 1) {
-            // consider add the city to the start point
-            let new_cost = if i == 0 {
-                euclidean_distance(&cities[city], &cities[initial_route[0]])
-            } else if i == initial_route.len() { // add the city to the tail
-                euclidean_distance(&cities[initial_route[initial_route.len() - 1]], &cities[city])
-            } else {
-                // add the city between 2 original cities
-                let prev = initial_route[i - 1];
-                let next = initial_route[i];
-                euclidean_distance(&cities[prev], &cities[city])
-                    + euclidean_distance(&cities[city], &cities[next])
-                    - euclidean_distance(&cities[prev], &cities[next])
-            };
-
-            if new_cost < lowest_cost {
-                lowest_cost = new_cost;
-                best_pos = i;
-            }
-        }
-
-        initial_route.insert(best_pos, city);
-    }
-
-    initial_route
-}
-#[allow(dead_code)]
-pub fn multi_2opt_random_insert(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+fn run(tour: &[usize], cities: &[City], warm: bool, deadline: Option<Instant>) -> (Vec<usize>, f64) {
     let n = tour.len();
     let possibilities: Vec<(usize, usize)> = (1..n - 1)
             .into_par_iter()
@@ -75,23 +37,30 @@ pub fn multi_2opt_random_insert(tour: &[usize], cities: &[City]) -> (Vec<usize>,
     // the number of threads can be changed later, the original number of threads is 16
     let better_res = (0..32)
     .into_par_iter()
-    .map(|_| {
-        
+    .filter_map(|start| {
+        // starts that have not begun by the deadline are skipped; the warm one always runs
+        if deadline.is_some_and(|d| Instant::now() >= d) && !(warm && start == 0) {
+            return None;
+        }
+
         // we may construct a better initial route using randon insertion
         // and to ensure that all the threads are optimized themselves without
         // doing redundent works, we may also do some randomize work on the start set
         let mut rng = thread_rng();
         let mut tmp = (0..n).collect::<Vec<_>>();
         tmp.shuffle(&mut rng);
-        let mut initial_route = if rng.gen_bool(0.5) {
+        let mut initial_route = if warm && start == 0 {
+            tour.to_vec()
+        } else if rng.gen_bool(0.5) {
             tmp
         } else {
-            get_initial_route(tmp, &cities)
+            insertion_tour(&tmp, &cities)
         };
         
         let mut route = initial_route.clone(); // original shuffle for current thread
-        let mut dis = get_tot_dis(&route, &cities);
+        let mut dis = CostTracker::new(&route, cities);
         while true { // continue using the repeated sampled based method
+            if deadline.is_some_and(|d| Instant::now() >= d) { break; }
             
             let sampled = possibilities.choose_multiple(&mut rng, sample_size)
                             .cloned()
@@ -107,42 +76,23 @@ pub fn multi_2opt_random_insert(tour: &[usize], cities: &[City]) -> (Vec<usize>,
                 // we can try to compare the original dis of the 2 edge and after swap the 2 edges. 
                 // but there is an issue, if we modify the original route immediately if the new route cost 
                 // less than original version, then the next operation is not on the original version
-                let non_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[i]])
-                        + euclidean_distance(&cities[route[j]], &cities[route[(j + 1) % n]]);
-                let after_swap = euclidean_distance(&cities[route[i - 1]], &cities[route[j]])
-                        + euclidean_distance(&cities[route[i]], &cities[route[(j + 1) % n]]);
+                let gain = two_opt_gain(&route, cities, i, j);
 
-                if non_swap - after_swap > 10.0 {
-                    can_modify.push((i, j, non_swap - after_swap));
+                if gain > 10.0 {
+                    can_modify.push((gain, i, j));
                 }
             }
-            // sort based on the thrid element
-            can_modify.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
 
-            let mut used = vec![false; n]; // define another vector to store the status of wether each postions has
-            // been chosen
-            let mut selected = vec![];
-
-            for &(i, j, diff) in &can_modify {
-                if used[i - 1] || used[i] || used[j] || used[(j + 1) % n] {
-                    continue;
-                }
-                selected.push((i, j));
-                used[i - 1] = true;
-                used[i] = true;
-                used[j] = true; 
-                used[(j + 1) % n] = true; // mark as used for these pairs of cities
-            }
-
-            for &(i, j) in &selected {
-                swap2edges(&mut route, i, j);
-            }
+            // apply the best independent swaps (nested ones too, crossing ones never)
+            let selected = select_independent(n, &mut can_modify, n);
+            let saved = apply_batch(&mut route, cities, &selected);
             // println!("the number of edges inside selected {:?}", selected.len());
-            let new_dis = get_tot_dis(&route, &cities);
-            if dis - new_dis < 1.0 {break;}
-            dis = new_dis;
+            dis.saved(saved);
+            dis.checkpoint(&route, cities);
+            if saved < 1.0 { break; }
         }
-        (route, dis)
+        let dis = dis.resync(&route, cities);
+        Some((route, dis))
     })
     .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
     .unwrap();
@@ -0,0 +1,315 @@
+/// Simulated annealing over 2-opt / Or-opt moves, with a parallel-tempering mode
+///
+/// Moves are proposed from k-nearest neighbour lists (the new edge always joins
+/// a city to one of its neighbours), their length change is computed in O(1)
+/// from the four / six touched edges, and accepted ones are applied to an
+/// `ArrayTour`. One "iteration" of the `StopCondition` is one epoch of
+/// `moves_per_epoch` proposals at a fixed temperature.
+///
+/// - `simulated_annealing`: a single chain under a `CoolingSchedule`
+/// - `parallel_tempering`: one replica per rayon thread on a fixed geometric
+///   temperature ladder, with neighbouring replicas exchanging temperatures
+///   after every epoch (Metropolis swap criterion)
+use rand::rngs::StdRng;
+use rand::Rng;
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::candidate_set::Candidates;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::local_search::*;
+use crate::all_versions::utils::*;
+
+/// How the temperature evolves between epochs
+#[allow(dead_code)]
+#[derive(Clone, Debug)]
+pub enum CoolingSchedule {
+    /// `T <- alpha * T`
+    Geometric { alpha: f64 },
+    /// Steer the acceptance ratio of worsening moves towards a target that decays
+    /// geometrically from `target_start` to `target_end` over the epoch budget
+    Adaptive { target_start: f64, target_end: f64 },
+    /// Geometric cooling, but after `patience` epochs without a new best the
+    /// temperature jumps back to `reheat_fraction * T0`
+    Reheating { alpha: f64, patience: usize, reheat_fraction: f64 },
+}
+
+#[derive(Clone, Debug)]
+pub struct SaConfig {
+    pub schedule: CoolingSchedule,
+    /// Starting temperature; `None` picks one that accepts an average worsening move with p = 0.5
+    pub initial_temperature: Option<f64>,
+    /// Proposals per epoch (0 = number of cities)
+    pub moves_per_epoch: usize,
+    /// Probability of proposing an Or-opt move instead of a 2-opt move
+    pub or_opt_probability: f64,
+    /// Candidate lists of the local search
+    pub candidates: Candidates,
+    /// Lowest rung of the parallel-tempering ladder, as a fraction of T0
+    pub ladder_min_fraction: f64,
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for SaConfig {
+    fn default() -> Self {
+        Self {
+            schedule: CoolingSchedule::Geometric { alpha: 0.97 },
+            initial_temperature: None,
+            moves_per_epoch: 0,
+            or_opt_probability: 0.3,
+            candidates: Candidates::Nearest(8),
+            ladder_min_fraction: 1e-3,
+            stop: StopCondition { max_iterations: 300, time_limit: None, patience: 60 },
+            seed: 121,
+        }
+    }
+}
+
+/// A move proposal: 2-opt `(a, c)` or Or-opt `(s1, len, left, reversed)`
+#[derive(Clone, Copy)]
+enum Move {
+    TwoOpt(usize, usize),
+    OrOpt(usize, usize, usize, bool),
+}
+
+/// One Markov chain: current tour and its length
+struct Chain {
+    t: ArrayTour,
+    cost: CostTracker,
+}
+
+impl Chain {
+    fn new(tour: &[usize], cities: &[City]) -> Self {
+        Self { t: ArrayTour::new(tour.to_vec()), cost: CostTracker::new(tour, cities) }
+    }
+
+    fn cost(&self) -> f64 {
+        self.cost.cost()
+    }
+
+    fn propose(&self, neighbors: &[Vec<usize>], or_opt_probability: f64, rng: &mut StdRng) -> Option<Move> {
+        let n = self.t.len();
+        let a = rng.gen_range(0..n);
+        let c = neighbors[a][rng.gen_range(0..neighbors[a].len())];
+
+        if rng.gen::<f64>() < or_opt_probability {
+            let seg_len = rng.gen_range(1..=3usize).min(n - 3);
+            // insert segment starting at `a` next to `c`: c a.. succ(c) or pred(c) ..a c
+            let reversed = rng.gen_bool(0.5);
+            let left = if reversed { self.t.pred(c) } else { c };
+            let inside = |x: usize| self.t.span(a, x) < seg_len;
+            if inside(left) || inside(self.t.succ(left)) {
+                return None;
+            }
+            Some(Move::OrOpt(a, seg_len, left, reversed))
+        } else {
+            // add edge (a, c) either as (a, c)+(succ a, succ c) or (pred a, pred c)+(a, c)
+            let (x, y) = if rng.gen_bool(0.5) { (a, c) } else { (self.t.pred(a), self.t.pred(c)) };
+            if x == y || self.t.succ(x) == y || self.t.succ(y) == x {
+                return None;
+            }
+            Some(Move::TwoOpt(x, y))
+        }
+    }
+
+    fn delta(&self, mv: Move, cities: &[City]) -> f64 {
+        match mv {
+            Move::TwoOpt(a, c) => self.t.two_opt_delta(a, c, cities),
+            Move::OrOpt(s1, len, left, rev) => self.t.move_segment_delta(s1, len, left, rev, cities),
+        }
+    }
+
+    fn apply(&mut self, mv: Move, delta: f64) {
+        match mv {
+            Move::TwoOpt(a, c) => self.t.two_opt_move(a, c),
+            Move::OrOpt(s1, len, left, rev) => self.t.move_segment(s1, len, left, rev),
+        }
+        self.cost.add(delta);
+    }
+
+    /// Run `moves` Metropolis steps at `temperature`; returns (worsening proposals, accepted worsening)
+    fn epoch(
+        &mut self,
+        cities: &[City],
+        neighbors: &[Vec<usize>],
+        config: &SaConfig,
+        temperature: f64,
+        moves: usize,
+        rng: &mut StdRng,
+    ) -> (usize, usize) {
+        let (mut uphill, mut accepted) = (0, 0);
+        for _ in 0..moves {
+            let Some(mv) = self.propose(neighbors, config.or_opt_probability, rng) else {
+                continue;
+            };
+            let delta = self.delta(mv, cities);
+            if delta <= 0.0 {
+                self.apply(mv, delta);
+            } else {
+                uphill += 1;
+                if rng.gen::<f64>() < (-delta / temperature).exp() {
+                    self.apply(mv, delta);
+                    accepted += 1;
+                }
+            }
+        }
+        self.cost.checkpoint(&self.t.order, cities);
+        (uphill, accepted)
+    }
+}
+
+/// T0 such that the mean worsening proposal is accepted with probability 0.5
+fn estimate_temperature(chain: &Chain, cities: &[City], neighbors: &[Vec<usize>], config: &SaConfig, rng: &mut StdRng) -> f64 {
+    let worsening: Vec<f64> = (0..1000)
+        .filter_map(|_| chain.propose(neighbors, config.or_opt_probability, rng))
+        .map(|mv| chain.delta(mv, cities))
+        .filter(|&d| d > 0.0)
+        .collect();
+    if worsening.is_empty() {
+        return 1.0;
+    }
+    let mean = worsening.iter().sum::<f64>() / worsening.len() as f64;
+    mean / std::f64::consts::LN_2
+}
+
+/// Polish the best tour to a local optimum and report its exact length
+fn finish(mut best: Vec<usize>, cities: &[City], neighbors: &[Vec<usize>]) -> (Vec<usize>, f64) {
+    local_search(&mut best, cities, neighbors, LocalSearchKind::Both, 0);
+    let cost = compute_total_distance(&best, cities);
+    (best, cost)
+}
+
+/// Single-chain simulated annealing starting from `tour`
+#[allow(dead_code)]
+pub fn simulated_annealing(tour: &[usize], cities: &[City], config: &SaConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 8 {
+        let cost = compute_total_distance(tour, cities);
+        return (tour.to_vec(), cost);
+    }
+    let start = Instant::now();
+    let neighbors = config.candidates.build(cities);
+    let moves = if config.moves_per_epoch == 0 { n } else { config.moves_per_epoch };
+    let mut rng = stream_rng(config.seed, 0, 0);
+
+    let mut chain = Chain::new(tour, cities);
+    let t0 = config
+        .initial_temperature
+        .unwrap_or_else(|| estimate_temperature(&chain, cities, &neighbors, config, &mut rng));
+    let mut temperature = t0;
+    let mut best = chain.t.order.clone();
+    let mut best_cost = chain.cost();
+    let mut since_improvement = 0;
+    let budget = if config.stop.max_iterations == 0 { 1000 } else { config.stop.max_iterations };
+
+    let mut epoch = 0;
+    while !config.stop.should_stop(epoch, since_improvement, start) {
+        let (uphill, accepted) = chain.epoch(cities, &neighbors, config, temperature, moves, &mut rng);
+
+        // best is sampled at epoch boundaries to keep copies off the hot path
+        if chain.cost() < best_cost - 1e-9 {
+            best_cost = chain.cost();
+            best.copy_from_slice(&chain.t.order);
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+
+        temperature = match config.schedule {
+            CoolingSchedule::Geometric { alpha } => temperature * alpha,
+            CoolingSchedule::Adaptive { target_start, target_end } => {
+                let progress = (epoch as f64 / budget as f64).min(1.0);
+                let target = target_start * (target_end / target_start).powf(progress);
+                let ratio = if uphill == 0 { 0.0 } else { accepted as f64 / uphill as f64 };
+                if ratio > target { temperature * 0.9 } else { temperature / 0.9 }
+            }
+            CoolingSchedule::Reheating { alpha, patience, reheat_fraction } => {
+                if patience > 0 && since_improvement > 0 && since_improvement % patience == 0 {
+                    t0 * reheat_fraction
+                } else {
+                    temperature * alpha
+                }
+            }
+        };
+
+        if epoch % 50 == 0 {
+            println!("Epoch {}: T = {:.4} | current = {:.2} | best = {:.2}", epoch, temperature, chain.cost(), best_cost);
+        }
+        epoch += 1;
+    }
+
+    finish(best, cities, &neighbors)
+}
+
+/// Parallel tempering: one replica per rayon thread, each at a fixed rung of a
+/// geometric ladder from T0 down to `ladder_min_fraction * T0`. After every
+/// epoch, neighbouring rungs swap tours with probability
+/// `min(1, exp((E_i - E_j) * (1/T_i - 1/T_j)))`.
+#[allow(dead_code)]
+pub fn parallel_tempering(tour: &[usize], cities: &[City], config: &SaConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 8 {
+        let cost = compute_total_distance(tour, cities);
+        return (tour.to_vec(), cost);
+    }
+    let start = Instant::now();
+    let neighbors = config.candidates.build(cities);
+    let moves = if config.moves_per_epoch == 0 { n } else { config.moves_per_epoch };
+    let replicas = rayon::current_num_threads().max(2);
+
+    let mut rng = stream_rng(config.seed, u64::MAX, 0);
+    let first = Chain::new(tour, cities);
+    let t0 = config
+        .initial_temperature
+        .unwrap_or_else(|| estimate_temperature(&first, cities, &neighbors, config, &mut rng));
+    let ratio = config.ladder_min_fraction.powf(1.0 / (replicas - 1) as f64);
+    let ladder: Vec<f64> = (0..replicas).map(|r| t0 * ratio.powi(r as i32)).collect();
+    // chains[r] always runs at ladder[r]; swaps exchange the chains between rungs
+    let mut chains: Vec<Chain> = (0..replicas).map(|_| Chain::new(tour, cities)).collect();
+
+    let mut best = tour.to_vec();
+    let mut best_cost = first.cost();
+    let mut since_improvement = 0;
+    let mut swaps_accepted = 0;
+
+    let mut round = 0;
+    while !config.stop.should_stop(round, since_improvement, start) {
+        chains.par_iter_mut().enumerate().for_each(|(r, chain)| {
+            let mut rng = stream_rng(config.seed, round as u64, r as u64);
+            chain.epoch(cities, &neighbors, config, ladder[r], moves, &mut rng);
+        });
+
+        let (r_best, chain) = chains
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.cost().partial_cmp(&b.1.cost()).unwrap())
+            .unwrap();
+        if chain.cost() < best_cost - 1e-9 {
+            best_cost = chain.cost();
+            best.copy_from_slice(&chains[r_best].t.order);
+            since_improvement = 0;
+        } else {
+            since_improvement += 1;
+        }
+
+        // Temperature exchange between neighbouring rungs (alternate even / odd pairs)
+        let mut rng = stream_rng(config.seed, round as u64, u64::MAX);
+        for r in (round % 2..replicas - 1).step_by(2) {
+            let exponent = (chains[r].cost() - chains[r + 1].cost()) * (1.0 / ladder[r] - 1.0 / ladder[r + 1]);
+            if exponent >= 0.0 || rng.gen::<f64>() < exponent.exp() {
+                chains.swap(r, r + 1);
+                swaps_accepted += 1;
+            }
+        }
+
+        if round % 50 == 0 {
+            println!(
+                "Round {}: best = {:.2} | coldest = {:.2} | swaps accepted = {}",
+                round, best_cost, chains[replicas - 1].cost(), swaps_accepted
+            );
+        }
+        round += 1;
+    }
+
+    finish(best, cities, &neighbors)
+}
@@ -0,0 +1,218 @@
+/// Spatial index over `City`: a static 2-d tree with point deletions
+///
+/// The tree is stored implicitly in one permutation of the city indices: the
+/// node of an index range `lo..hi` is its middle element, with the smaller
+/// coordinates (x at even depths, y at odd ones) to its left. Building is a
+/// recursive median split, run in parallel with `rayon::join` on large ranges.
+///
+/// Deleting a city only marks it and decrements the live-point count of the
+/// ranges on its path, so subtrees whose cities are all deleted are skipped by
+/// every query. This serves "nearest unvisited city" for nearest-neighbour
+/// construction, as well as k-nearest (candidate lists) and radius queries.
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::all_versions::utils::*;
+
+/// Ranges below this size are built sequentially
+const PAR_BUILD_MIN: usize = 10_000;
+
+pub struct KdTree<'a> {
+    cities: &'a [City],
+    points: Vec<usize>,
+    /// `alive[mid]`: live cities in the range whose node is `mid`
+    alive: Vec<usize>,
+    deleted: Vec<bool>,
+    /// `slot[city]`: index of `city` in `points`
+    slot: Vec<usize>,
+}
+
+fn coord(c: &City, axis: usize) -> f64 {
+    if axis == 0 { c.x } else { c.y }
+}
+
+/// Max-heap entry for the k-nearest search (farthest candidate on top)
+struct Candidate(f64, usize);
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0).then(self.1.cmp(&other.1))
+    }
+}
+
+#[allow(dead_code)]
+impl<'a> KdTree<'a> {
+    pub fn new(cities: &'a [City]) -> Self {
+        let n = cities.len();
+        let mut points: Vec<usize> = (0..n).collect();
+        Self::build(cities, &mut points, 0);
+        let mut slot = vec![0; n];
+        for (i, &p) in points.iter().enumerate() {
+            slot[p] = i;
+        }
+        let mut alive = vec![0; n];
+        Self::count(&mut alive, 0, n);
+        Self { cities, points, alive, deleted: vec![false; n], slot }
+    }
+
+    fn build(cities: &[City], points: &mut [usize], depth: usize) {
+        if points.len() <= 1 {
+            return;
+        }
+        let mid = points.len() / 2;
+        let axis = depth % 2;
+        points.select_nth_unstable_by(mid, |&a, &b| coord(&cities[a], axis).total_cmp(&coord(&cities[b], axis)));
+        let (left, right) = points.split_at_mut(mid);
+        let right = &mut right[1..];
+        if left.len() >= PAR_BUILD_MIN {
+            rayon::join(|| Self::build(cities, left, depth + 1), || Self::build(cities, right, depth + 1));
+        } else {
+            Self::build(cities, left, depth + 1);
+            Self::build(cities, right, depth + 1);
+        }
+    }
+
+    fn count(alive: &mut [usize], lo: usize, hi: usize) {
+        if lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            alive[mid] = hi - lo;
+            Self::count(alive, lo, mid);
+            Self::count(alive, mid + 1, hi);
+        }
+    }
+
+    /// Number of cities not deleted
+    pub fn len(&self) -> usize {
+        if self.points.is_empty() { 0 } else { self.alive[self.points.len() / 2] }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, city: usize) -> bool {
+        !self.deleted[city]
+    }
+
+    /// Delete `city` from all further query results
+    pub fn remove(&mut self, city: usize) {
+        if std::mem::replace(&mut self.deleted[city], true) {
+            return;
+        }
+        let target = self.slot[city];
+        let (mut lo, mut hi) = (0, self.points.len());
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            self.alive[mid] -= 1;
+            match target.cmp(&mid) {
+                Ordering::Equal => break,
+                Ordering::Less => hi = mid,
+                Ordering::Greater => lo = mid + 1,
+            }
+        }
+    }
+
+    /// Offer the live cities to `f` as `Some((city, distance to q))`, nearer
+    /// side first. `f(None)` must return the current search radius: a far side
+    /// is skipped when its splitting line lies beyond it.
+    fn visit(&self, q: &City, lo: usize, hi: usize, depth: usize, f: &mut impl FnMut(Option<(usize, f64)>) -> f64) {
+        if lo >= hi {
+            return;
+        }
+        let mid = lo + (hi - lo) / 2;
+        if self.alive[mid] == 0 {
+            return;
+        }
+        let p = self.points[mid];
+        if !self.deleted[p] {
+            f(Some((p, euclidean_distance(q, &self.cities[p]))));
+        }
+        let axis = depth % 2;
+        let diff = coord(q, axis) - coord(&self.cities[p], axis);
+        let (near, far) = if diff < 0.0 { ((lo, mid), (mid + 1, hi)) } else { ((mid + 1, hi), (lo, mid)) };
+        self.visit(q, near.0, near.1, depth + 1, f);
+        if diff.abs() <= f(None) {
+            self.visit(q, far.0, far.1, depth + 1, f);
+        }
+    }
+
+    /// Closest live city to the point `q`
+    pub fn nearest_to(&self, q: &City) -> Option<usize> {
+        let mut best = (f64::INFINITY, None);
+        self.visit(q, 0, self.points.len(), 0, &mut |offer| {
+            if let Some((p, d)) = offer {
+                if d < best.0 {
+                    best = (d, Some(p));
+                }
+            }
+            best.0
+        });
+        best.1
+    }
+
+    /// Closest live city to `city` other than itself
+    pub fn nearest(&self, city: usize) -> Option<usize> {
+        let mut best = (f64::INFINITY, None);
+        self.visit(&self.cities[city], 0, self.points.len(), 0, &mut |offer| {
+            if let Some((p, d)) = offer {
+                if p != city && d < best.0 {
+                    best = (d, Some(p));
+                }
+            }
+            best.0
+        });
+        best.1
+    }
+
+    /// Up to `k` live cities closest to the point `q`, closest first
+    pub fn k_nearest(&self, q: &City, k: usize) -> Vec<usize> {
+        self.k_nearest_where(q, k, |_| true)
+    }
+
+    /// Up to `k` live cities accepted by `keep` closest to the point `q`,
+    /// closest first. The search only narrows once `k` cities are accepted.
+    pub fn k_nearest_where(&self, q: &City, k: usize, keep: impl Fn(usize) -> bool) -> Vec<usize> {
+        if k == 0 {
+            return Vec::new();
+        }
+        let mut heap: BinaryHeap<Candidate> = BinaryHeap::with_capacity(k + 1);
+        self.visit(q, 0, self.points.len(), 0, &mut |offer| {
+            if let Some((p, d)) = offer.filter(|&(p, _)| keep(p)) {
+                heap.push(Candidate(d, p));
+                if heap.len() > k {
+                    heap.pop();
+                }
+            }
+            if heap.len() < k { f64::INFINITY } else { heap.peek().unwrap().0 }
+        });
+        heap.into_sorted_vec().into_iter().map(|c| c.1).collect()
+    }
+
+    /// Live cities within `radius` of the point `q`, closest first
+    pub fn within_radius(&self, q: &City, radius: f64) -> Vec<usize> {
+        let mut found = Vec::new();
+        self.visit(q, 0, self.points.len(), 0, &mut |offer| {
+            if let Some((p, d)) = offer {
+                if d <= radius {
+                    found.push(Candidate(d, p));
+                }
+            }
+            radius
+        });
+        found.sort();
+        found.into_iter().map(|c| c.1).collect()
+    }
+}
@@ -0,0 +1,210 @@
+/// Tabu search over the 2-opt neighbourhood
+///
+/// Every iteration evaluates the 2-opt gains of (a sample of) the neighbourhood
+/// in parallel, sorts
+/// them best-first and applies the first admissible move, even when it makes
+/// the tour longer. A move is tabu if it would re-add an edge removed within
+/// the last `tenure` iterations or remove an edge added within that window;
+/// aspiration lets a tabu move through when it produces a new best tour. After `diversify_after` iterations without
+/// a new best, the search restarts from the best tour perturbed by a double
+/// bridge, with an empty tabu list.
+///
+/// With `candidates > 0` only the moves adding an edge between a city and one
+/// of its nearest neighbours are evaluated, O(n k) per iteration; with 0 the
+/// whole rows are, as `par_topk` does with `neighborhoods::two_opt_gains`,
+/// O(n²) per iteration. Expired tabu entries are evicted every `tenure`
+/// iterations, so the list stays at O(tenure) edges.
+use rand::seq::index::sample;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Instant;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::local_search::{neighbor_lists, position_index};
+use crate::all_versions::neighborhoods::{reverse_shorter_side, two_opt_gain, two_opt_gains};
+use crate::all_versions::utils::*;
+
+#[derive(Clone, Debug)]
+pub struct TabuConfig {
+    /// Iterations an edge stays tabu after being added or removed
+    pub tenure: usize,
+    /// Fraction of the first-edge positions `i` evaluated per iteration (1.0 = full neighbourhood)
+    pub sample_ratio: f64,
+    /// Nearest neighbours per city a move may connect it to (0 = every `j` of a row)
+    pub candidates: usize,
+    /// Iterations without a new best before a double-bridge restart from the best (0 = never)
+    pub diversify_after: usize,
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for TabuConfig {
+    fn default() -> Self {
+        Self {
+            tenure: 15,
+            sample_ratio: 1.0,
+            candidates: 10,
+            diversify_after: 100,
+            stop: StopCondition { max_iterations: 1000, time_limit: None, patience: 400 },
+            seed: 121,
+        }
+    }
+}
+
+type Edge = (usize, usize);
+
+fn edge(a: usize, b: usize) -> Edge {
+    (a.min(b), a.max(b))
+}
+
+/// Edge -> last iteration at which it is still tabu
+struct TabuList {
+    until: HashMap<Edge, usize>,
+}
+
+impl TabuList {
+    fn is_tabu(&self, e: Edge, iteration: usize) -> bool {
+        self.until.get(&e).is_some_and(|&until| iteration <= until)
+    }
+
+    fn forbid(&mut self, e: Edge, until: usize) {
+        self.until.insert(e, until);
+    }
+
+    /// Drop the entries no longer tabu at `iteration`
+    fn evict(&mut self, iteration: usize) {
+        self.until.retain(|_, &mut until| iteration <= until);
+    }
+}
+
+/// Removed and added edges of the move reversing `tour[i..=j]`
+fn move_edges(tour: &[usize], i: usize, j: usize) -> ([Edge; 2], [Edge; 2]) {
+    let n = tour.len();
+    let (a, b, c, d) = (tour[i - 1], tour[i], tour[j], tour[(j + 1) % n]);
+    ([edge(a, b), edge(c, d)], [edge(a, c), edge(b, d)])
+}
+
+/// Gain of the moves `(i, j)`, `i` in `rows`, whose added edge `tour[i - 1]`
+/// -> `tour[j]` or `tour[i]` -> `tour[j + 1]` joins a city to one of its
+/// `neighbors`. A move can be listed twice, once per added edge.
+fn candidate_gains(tour: &[usize], pos: &[usize], cities: &[City], rows: &[usize], neighbors: &[Vec<usize>]) -> Vec<(f64, usize, usize)> {
+    let n = tour.len();
+    rows.par_iter()
+        .flat_map_iter(|&i| {
+            let via_a = neighbors[tour[i - 1]].iter().map(|&c| pos[c]);
+            let via_b = neighbors[tour[i]].iter().map(|&d| (pos[d] + n - 1) % n);
+            via_a.chain(via_b).filter(move |&j| j > i).map(move |j| (two_opt_gain(tour, cities, i, j), i, j))
+        })
+        .collect()
+}
+
+/// Moves in descending gain order (ties by position). Only the top `HEAD` are
+/// sorted up front; the tail is sorted if the scan gets past them, which is rare
+/// since at most `4 * tenure` edges are tabu at a time.
+fn best_first(moves: &mut [(f64, usize, usize)]) -> impl Iterator<Item = (f64, usize, usize)> + '_ {
+    const HEAD: usize = 256;
+    let order = |a: &(f64, usize, usize), b: &(f64, usize, usize)| {
+        b.0.partial_cmp(&a.0).unwrap_or(Ordering::Equal).then((a.1, a.2).cmp(&(b.1, b.2)))
+    };
+    let head = HEAD.min(moves.len());
+    if head < moves.len() {
+        moves.select_nth_unstable_by(head, order);
+    }
+    let (top, rest) = moves.split_at_mut(head);
+    top.sort_unstable_by(order);
+    let mut rest = Some(rest);
+    top.iter().copied().chain(std::iter::from_fn(move || {
+        let r = rest.take()?;
+        r.sort_unstable_by(order);
+        Some(r.iter().copied())
+    }).flatten())
+}
+
+/// Tabu search starting from `tour`
+#[allow(dead_code)]
+pub fn tabu_search(tour: &[usize], cities: &[City], config: &TabuConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    if n < 5 {
+        let cost = compute_total_distance(tour, cities);
+        return (tour.to_vec(), cost);
+    }
+    let start = Instant::now();
+    let mut rng = stream_rng(config.seed, 0, 0);
+    let rows_total = n - 2;
+    let rows_sampled = ((rows_total as f64 * config.sample_ratio).ceil() as usize).clamp(1, rows_total);
+
+    let mut current = tour.to_vec();
+    let mut cost = CostTracker::new(&current, cities);
+    let mut best = current.clone();
+    let mut best_cost = cost.cost();
+    let mut tabu = TabuList { until: HashMap::new() };
+    let neighbors = (config.candidates > 0).then(|| neighbor_lists(cities, config.candidates));
+
+    let mut iteration = 0;
+    let mut since_improvement = 0;
+    let mut stagnation = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) {
+        let rows: Vec<usize> = if rows_sampled == rows_total {
+            (1..n - 1).collect()
+        } else {
+            let mut rows: Vec<usize> = sample(&mut rng, rows_total, rows_sampled).into_iter().map(|r| r + 1).collect();
+            rows.sort_unstable();
+            rows
+        };
+        let mut moves = match &neighbors {
+            Some(neighbors) => candidate_gains(&current, &position_index(&current), cities, &rows, neighbors),
+            None => two_opt_gains(&current, cities, &rows),
+        };
+
+        // Best admissible move, scanning best-first; (1, n - 1) re-adds the edges it removes
+        let admissible = |&(gain, i, j): &(f64, usize, usize)| {
+            if i == 1 && j == n - 1 {
+                return false;
+            }
+            let aspiration = cost.cost() - gain < best_cost - 1e-9;
+            let (removed, added) = move_edges(&current, i, j);
+            aspiration || !removed.iter().chain(&added).any(|&e| tabu.is_tabu(e, iteration))
+        };
+        let chosen = best_first(&mut moves).find(admissible);
+
+        if let Some((gain, i, j)) = chosen {
+            let (removed, added) = move_edges(&current, i, j);
+            for e in removed.into_iter().chain(added) {
+                tabu.forbid(e, iteration + config.tenure);
+            }
+            reverse_shorter_side(&mut current, i, j);
+            cost.saved(gain);
+            cost.checkpoint(&current, cities);
+        }
+
+        if cost.cost() < best_cost - 1e-9 {
+            best_cost = cost.cost();
+            best.copy_from_slice(&current);
+            since_improvement = 0;
+            stagnation = 0;
+        } else {
+            since_improvement += 1;
+            stagnation += 1;
+        }
+
+        if config.diversify_after > 0 && stagnation >= config.diversify_after {
+            current.copy_from_slice(&best);
+            double_bridge_with_rng(&mut current, &mut rng);
+            cost = CostTracker::new(&current, cities);
+            tabu.until.clear();
+            stagnation = 0;
+            println!("🔀 Iteration {}: diversify from best = {:.2}", iteration, best_cost);
+        }
+
+        if iteration % config.tenure.max(1) == 0 {
+            tabu.evict(iteration);
+        }
+        if iteration % 100 == 0 {
+            println!("Iteration {}: current = {:.2} | best = {:.2}", iteration, cost.cost(), best_cost);
+        }
+        iteration += 1;
+    }
+
+    let final_cost = compute_total_distance(&best, cities);
+    (best, final_cost)
+}
@@ -0,0 +1,101 @@
+/// Tour-segment parallel 2-opt with rotating offsets
+///
+/// The tour array is cut into `T` contiguous segments of equal length and each
+/// segment is 2-opted in parallel as an open path with its endpoints fixed
+/// (`neighborhoods::two_opt_path`, on the shared `two_opt_gain` /
+/// `reverse_segment` move). A move inside one segment only touches that
+/// segment's slice, so no moves conflict. `par_topk` instead needs the
+/// conflict checks of `batch_moves` to pick independent moves from one global
+/// evaluation.
+///
+/// After each round the tour is rotated by `1 / rotations` of a segment, which
+/// moves every cut point so the edges frozen at the cuts become interior.
+/// Moves longer than a segment are never found at a fixed `T`. Once `rotations`
+/// rounds in a row gain nothing, `T` is halved, down to `min_segments`; at 1
+/// the last rounds are a plain sequential 2-opt, so the result is a full 2-opt
+/// local optimum like `two_opt_seq`.
+use rayon::prelude::*;
+use std::time::Instant;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::two_opt_path;
+use crate::all_versions::utils::*;
+
+/// Cities per segment when `segments` is 0
+const CITIES_PER_SEGMENT: usize = 50;
+
+#[derive(Clone, Debug)]
+pub struct SegmentConfig {
+    /// Initial number of segments; 0 = max(4 x threads, n / 50)
+    pub segments: usize,
+    /// Segments of the last phase (1 = finish with a full 2-opt)
+    pub min_segments: usize,
+    /// Offsets per segment length: the cut points move by `1 / rotations` of
+    /// a segment per round, and this many rounds without gain halve `T`
+    pub rotations: usize,
+    /// One iteration = one round (all segments once)
+    pub stop: StopCondition,
+}
+
+impl Default for SegmentConfig {
+    fn default() -> Self {
+        Self {
+            segments: 0,
+            min_segments: 1,
+            rotations: 2,
+            stop: StopCondition { max_iterations: 0, time_limit: None, patience: 0 },
+        }
+    }
+}
+
+/// Tour-segment parallel 2-opt starting from `tour`
+#[allow(dead_code)]
+pub fn segment_parallel_2opt(tour: &[usize], cities: &[City], config: &SegmentConfig) -> (Vec<usize>, f64) {
+    let n = tour.len();
+    let mut current = tour.to_vec();
+    if n < 8 {
+        let cost = compute_total_distance(&current, cities);
+        return (current, cost);
+    }
+    let start = Instant::now();
+    let rotations = config.rotations.max(1);
+    let min_segments = config.min_segments.max(1);
+    let mut segments = match config.segments {
+        0 => (4 * rayon::current_num_threads()).max(n / CITIES_PER_SEGMENT),
+        t => t,
+    }
+    .min(n / 4)
+    .max(min_segments);
+    let mut cost = CostTracker::new(&current, cities);
+
+    let mut round = 0;
+    let mut since_improvement = 0;
+    let mut idle = 0;
+    while !config.stop.should_stop(round, since_improvement, start) {
+        let len = n.div_ceil(segments);
+        let saved: f64 = current.par_chunks_mut(len).map(|segment| two_opt_path(segment, cities)).sum();
+        current.rotate_left((len / rotations).max(1));
+
+        cost.saved(saved);
+        cost.checkpoint(&current, cities);
+        if saved > 1e-9 {
+            since_improvement = 0;
+            idle = 0;
+        } else {
+            since_improvement += 1;
+            idle += 1;
+        }
+        if idle >= rotations {
+            if segments <= min_segments {
+                break;
+            }
+            segments = (segments / 2).max(min_segments);
+            idle = 0;
+        }
+        if round % 10 == 0 {
+            println!("Round {}: cost = {:.2} ({} segments of {})", round, cost.cost(), segments, n.div_ceil(segments));
+        }
+        round += 1;
+    }
+    let cost = cost.resync(&current, cities);
+    (current, cost)
+}
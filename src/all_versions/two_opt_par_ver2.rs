@@ -8,7 +8,9 @@ use std::cmp::Ordering;
 use rand::thread_rng;
 use rand::seq::SliceRandom;
 use rand::Rng;
-use crate::all_versions::utils::{City, compute_total_distance, euclidean_distance};
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::{reverse_shorter_side, two_opt_gain};
+use crate::all_versions::utils::City;
 
 // high level idea of how to optimize the original parallel version of 2-opt
 
@@ -26,21 +28,6 @@ use crate::all_versions::utils::{City, compute_total_distance, euclidean_distanc
 // After that, we can do some extra computation to get better result
 
 // TODO: if there is a way to parallize the calculation of the total distance among the current shuffle
-fn get_tot_dis(tour: &[usize], cities: &[City]) -> f64 {
-    // using the windown to generate an iterator to split the array into 2 subarry
-    let dist_sum: f64 = tour
-        .windows(2) 
-        .map(|w| euclidean_distance(&cities[w[0]], &cities[w[1]]))
-        .sum();
-
-    let return_dist = euclidean_distance(&cities[tour[tour.len() - 1]], &cities[tour[0]]);
-    dist_sum + return_dist
-}
-
-fn swap2edges(tour: &mut [usize], i: usize, j: usize) {
-    tour[i..=j].reverse();
-}
-
 #[allow(dead_code)]
 pub fn two_opt_par_ver2(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
     let n = tour.len();
@@ -60,7 +47,7 @@ pub fn two_opt_par_ver2(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
             tour.shuffle(&mut rng); 
             
             let mut route = tour.clone();
-            let mut dis = get_tot_dis(&route, &cities);
+            let mut dis = CostTracker::new(&route, cities);
 
             for _ in 0..repeat_times {
                 let sampled = possibilities.choose_multiple(&mut rng, sample_size)
@@ -68,15 +55,17 @@ pub fn two_opt_par_ver2(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
                     .collect::<Vec<_>>();
 
                 for &(i, j) in &sampled {
-                    let mut new_route = route.clone();
-                    swap2edges(&mut new_route, i, j);
-                    let new_dis = get_tot_dis(&new_route, &cities);
-                    if dis - new_dis > 1.0 {
-                        dis = new_dis;
-                        route = new_route;
+                    // the swap only changes two edges, no need to clone and
+                    // measure the whole route
+                    let gain = two_opt_gain(&route, cities, i, j);
+                    if gain > 1.0 {
+                        reverse_shorter_side(&mut route, i, j);
+                        dis.saved(gain);
+                        dis.checkpoint(&route, cities);
                     }
                 }
             }
+            let dis = dis.resync(&route, cities);
             (route, dis)
         })
         .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
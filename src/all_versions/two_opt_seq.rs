@@ -1,12 +1,18 @@
+use std::time::Instant;
 use crate::all_versions::utils::*;
 
 /// 0️⃣ Standard sequential 2-opt algorithm for TSP
 /// Iteratively reverses segments to reduce total tour distance.
 pub fn two_opt_seq(tour: &[usize], cities: &[City]) -> (Vec<usize>, f64) {
+    two_opt_seq_until(tour, cities, None)
+}
+
+/// `two_opt_seq` that stops after the first pass ending past `deadline`
+pub fn two_opt_seq_until(tour: &[usize], cities: &[City], deadline: Option<Instant>) -> (Vec<usize>, f64) {
     let mut improved = true;
     let mut new_tour = tour.to_vec();
 
-    while improved {
+    while improved && deadline.is_none_or(|d| Instant::now() < d) {
         improved = false;
 
         for i in 1..new_tour.len() - 1 {
@@ -8,6 +8,7 @@ use rand::Rng;
 use rand::thread_rng;
 use rayon::prelude::*;
 use std::collections::HashSet;
+use std::time::{Duration, Instant};
 
 /// Representation of a city in 2D space
 #[derive(Clone, Debug)]
@@ -27,6 +28,25 @@ pub fn generate_cities(n: usize, seed: u64) -> Vec<City> {
         .collect()
 }
 
+/// Generate `n` cities in `clusters` Gaussian clusters (spread 1% of the
+/// 1000 x 1000 square) around uniform random centres
+#[allow(dead_code)]
+pub fn generate_clustered_cities(n: usize, clusters: usize, seed: u64) -> Vec<City> {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    let centres: Vec<(f64, f64)> = (0..clusters.max(1))
+        .map(|_| (rng.gen_range(0.0..1000.0), rng.gen_range(0.0..1000.0)))
+        .collect();
+    (0..n)
+        .map(|_| {
+            let (cx, cy) = centres[rng.gen_range(0..centres.len())];
+            // Box-Muller
+            let r = (-2.0 * (1.0 - rng.gen::<f64>()).ln()).sqrt() * 10.0;
+            let angle = rng.gen_range(0.0..std::f64::consts::TAU);
+            City { x: cx + r * angle.cos(), y: cy + r * angle.sin() }
+        })
+        .collect()
+}
+
 /// Euclidean distance between two cities
 pub fn euclidean_distance(a: &City, b: &City) -> f64 {
     ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
@@ -47,9 +67,14 @@ pub fn shuffle_tour(tour: &mut Vec<usize>) {
 }
 
 pub fn two_opt(tour: &[usize], cities: &[City]) -> Vec<usize> {
+    two_opt_until(tour, cities, None)
+}
+
+/// `two_opt` that stops after the first pass ending past `deadline`
+pub fn two_opt_until(tour: &[usize], cities: &[City], deadline: Option<Instant>) -> Vec<usize> {
     let mut improved = true;
     let mut new_tour = tour.to_vec();
-    while improved {
+    while improved && deadline.is_none_or(|d| Instant::now() < d) {
         improved = false;
         for i in 1..new_tour.len() - 2 {
             for j in i + 1..new_tour.len() - 1 {
@@ -71,6 +96,29 @@ pub fn two_opt(tour: &[usize], cities: &[City]) -> Vec<usize> {
     new_tour
 }
 
+/// When an iterative solver should stop. Any limit set to 0 / `None` is ignored;
+/// what counts as one iteration is defined by the solver (epoch, kick, round...).
+#[derive(Clone, Debug)]
+pub struct StopCondition {
+    pub max_iterations: usize,
+    pub time_limit: Option<Duration>,
+    /// Iterations without improving the best tour
+    pub patience: usize,
+}
+
+impl StopCondition {
+    #[allow(dead_code)]
+    pub fn iterations(max_iterations: usize) -> Self {
+        Self { max_iterations, time_limit: None, patience: 0 }
+    }
+
+    pub fn should_stop(&self, iteration: usize, since_improvement: usize, start: Instant) -> bool {
+        (self.max_iterations > 0 && iteration >= self.max_iterations)
+            || (self.patience > 0 && since_improvement >= self.patience)
+            || self.time_limit.is_some_and(|limit| start.elapsed() >= limit)
+    }
+}
+
 /// Representation of a TSP solution with fitness (inverse of total distance)
 #[derive(Clone)]
 pub struct Individual {
@@ -92,16 +140,63 @@ impl Individual {
 
 /// Simple swap mutation for a tour
 pub fn swap_mutation(tour: &mut Vec<usize>) {
-    let mut rng = thread_rng();
+    swap_mutation_with_rng(tour, &mut thread_rng());
+}
+
+/// Swap mutation driven by a caller-supplied RNG (for seeded, reproducible runs)
+pub fn swap_mutation_with_rng<R: Rng + ?Sized>(tour: &mut [usize], rng: &mut R) {
     let i = rng.gen_range(0..tour.len());
     let j = rng.gen_range(0..tour.len());
     tour.swap(i, j);
 }
 
+/// Inversion mutation: reverse a random segment (a random 2-opt move)
+pub fn inversion_mutation_with_rng<R: Rng + ?Sized>(tour: &mut [usize], rng: &mut R) {
+    let (mut i, mut j) = (rng.gen_range(0..tour.len()), rng.gen_range(0..tour.len()));
+    if i > j {
+        std::mem::swap(&mut i, &mut j);
+    }
+    tour[i..=j].reverse();
+}
+
+/// Insertion mutation: move one random city to a random position
+pub fn insertion_mutation_with_rng<R: Rng + ?Sized>(tour: &mut Vec<usize>, rng: &mut R) {
+    let city = tour.remove(rng.gen_range(0..tour.len()));
+    let to = rng.gen_range(0..=tour.len());
+    tour.insert(to, city);
+}
+
+/// Double-bridge kick: cut the tour into A B C D and reconnect as A C B D.
+/// A 4-opt move that 2-opt cannot undo in one step; needs at least 4 cities.
+pub fn double_bridge_with_rng<R: Rng + ?Sized>(tour: &mut Vec<usize>, rng: &mut R) {
+    let n = tour.len();
+    if n < 4 {
+        return;
+    }
+    let mut cuts = rand::seq::index::sample(rng, n - 1, 3).into_vec();
+    cuts.sort_unstable();
+    let (p1, p2, p3) = (cuts[0] + 1, cuts[1] + 1, cuts[2] + 1);
+
+    let mut out = Vec::with_capacity(n);
+    out.extend_from_slice(&tour[..p1]);
+    out.extend_from_slice(&tour[p2..p3]);
+    out.extend_from_slice(&tour[p1..p2]);
+    out.extend_from_slice(&tour[p3..]);
+    *tour = out;
+}
+
 /// Order Crossover (OX) operator for two parent tours
 pub fn order_crossover(parent1: &[usize], parent2: &[usize]) -> Vec<usize> {
+    order_crossover_with_rng(parent1, parent2, &mut thread_rng())
+}
+
+/// Order Crossover (OX) driven by a caller-supplied RNG
+pub fn order_crossover_with_rng<R: Rng + ?Sized>(
+    parent1: &[usize],
+    parent2: &[usize],
+    rng: &mut R,
+) -> Vec<usize> {
     let len = parent1.len();
-    let mut rng = thread_rng();
     let (mut start, mut end) = (rng.gen_range(0..len), rng.gen_range(0..len));
     if start > end {
         std::mem::swap(&mut start, &mut end);
@@ -132,11 +227,167 @@ pub fn order_crossover(parent1: &[usize], parent2: &[usize]) -> Vec<usize> {
     child.into_iter().map(|x| x.unwrap()).collect()
 }
 
+/// Partially Mapped Crossover (PMX): copy a segment of `parent1`, fill the rest
+/// from `parent2`, resolving conflicts through the segment's position mapping
+pub fn pmx_crossover_with_rng<R: Rng + ?Sized>(
+    parent1: &[usize],
+    parent2: &[usize],
+    rng: &mut R,
+) -> Vec<usize> {
+    let len = parent1.len();
+    let (mut start, mut end) = (rng.gen_range(0..len), rng.gen_range(0..len));
+    if start > end {
+        std::mem::swap(&mut start, &mut end);
+    }
+
+    let mut pos_in_p1 = vec![0; len];
+    for (i, &c) in parent1.iter().enumerate() {
+        pos_in_p1[c] = i;
+    }
+    let mut child = parent2.to_vec();
+    let mut in_segment = vec![false; len];
+    child[start..=end].copy_from_slice(&parent1[start..=end]);
+    for &c in &parent1[start..=end] {
+        in_segment[c] = true;
+    }
+
+    for i in (0..start).chain(end + 1..len) {
+        // follow the mapping p1[k] -> p2[k] until the city is free
+        let mut c = parent2[i];
+        while in_segment[c] {
+            c = parent2[pos_in_p1[c]];
+        }
+        child[i] = c;
+    }
+    child
+}
+
+/// Cycle Crossover (CX): every city keeps the position it has in one of the parents
+pub fn cycle_crossover(parent1: &[usize], parent2: &[usize]) -> Vec<usize> {
+    let len = parent1.len();
+    let mut pos_in_p1 = vec![0; len];
+    for (i, &c) in parent1.iter().enumerate() {
+        pos_in_p1[c] = i;
+    }
+    let mut child = vec![usize::MAX; len];
+    let mut from_p1 = true;
+    for start in 0..len {
+        if child[start] != usize::MAX {
+            continue;
+        }
+        // alternate parents cycle by cycle
+        let mut i = start;
+        loop {
+            child[i] = if from_p1 { parent1[i] } else { parent2[i] };
+            i = pos_in_p1[parent2[i]];
+            if i == start {
+                break;
+            }
+        }
+        from_p1 = !from_p1;
+    }
+    child
+}
+
 /// Tournament selection (select best of `k` random individuals)
 pub fn tournament_selection<'a>(population: &'a [Individual], k: usize) -> &'a Individual {
-    let mut rng = thread_rng();
+    tournament_selection_with_rng(population, k, &mut thread_rng())
+}
+
+/// Tournament selection driven by a caller-supplied RNG
+pub fn tournament_selection_with_rng<'a, R: Rng + ?Sized>(
+    population: &'a [Individual],
+    k: usize,
+    rng: &mut R,
+) -> &'a Individual {
     (0..k)
         .map(|_| &population[rng.gen_range(0..population.len())])
         .max_by(|a, b| a.fitness.partial_cmp(&b.fitness).unwrap())
         .unwrap()
 }
+
+/// Deterministic per-task RNG derived from a base seed and two stream indices
+/// (e.g. generation and child slot), so parallel work stays reproducible
+/// regardless of how rayon schedules it.
+pub fn stream_rng(seed: u64, a: u64, b: u64) -> StdRng {
+    // SplitMix64 finalizer to decorrelate neighbouring streams
+    let mut z = seed
+        .wrapping_add(a.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(b.wrapping_mul(0xD1B5_4A32_D192_ED03));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    StdRng::seed_from_u64(z ^ (z >> 31))
+}
+
+/// Which individuals receive the periodic 2-opt refinement in the GAs
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RefinePolicy {
+    /// The `n` fittest individuals
+    BestN,
+    /// `n` individuals drawn uniformly at random
+    RandomN,
+    /// Greedy max-min edge distance, starting from the fittest individual
+    MostDiverseN,
+}
+
+/// Number of edges of `tour` that are not present in the tour described by `succ`/`pred`
+fn edge_distance(tour: &[usize], succ: &[usize], pred: &[usize]) -> usize {
+    (0..tour.len())
+        .filter(|&i| {
+            let (a, b) = (tour[i], tour[(i + 1) % tour.len()]);
+            succ[a] != b && pred[a] != b
+        })
+        .count()
+}
+
+/// Pick the population indices to refine under `policy`
+pub fn select_for_refinement<R: Rng + ?Sized>(
+    population: &[Individual],
+    n: usize,
+    policy: RefinePolicy,
+    rng: &mut R,
+) -> Vec<usize> {
+    let n = n.min(population.len());
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut by_fitness: Vec<usize> = (0..population.len()).collect();
+    by_fitness.sort_by(|&a, &b| population[b].fitness.partial_cmp(&population[a].fitness).unwrap());
+
+    match policy {
+        RefinePolicy::BestN => by_fitness.truncate(n),
+        RefinePolicy::RandomN => {
+            by_fitness = rand::seq::index::sample(rng, population.len(), n).into_vec();
+        }
+        RefinePolicy::MostDiverseN => {
+            let mut selected = vec![by_fitness[0]];
+            // min edge distance from each individual to the selected set
+            let mut min_dist = vec![usize::MAX; population.len()];
+            while selected.len() < n {
+                let last = &population[*selected.last().unwrap()].tour;
+                let mut succ = vec![0; last.len()];
+                let mut pred = vec![0; last.len()];
+                for i in 0..last.len() {
+                    succ[last[i]] = last[(i + 1) % last.len()];
+                    pred[last[(i + 1) % last.len()]] = last[i];
+                }
+                min_dist
+                    .par_iter_mut()
+                    .zip(population.par_iter())
+                    .for_each(|(d, ind)| *d = (*d).min(edge_distance(&ind.tour, &succ, &pred)));
+
+                // ties broken by fitness order, so the choice is deterministic
+                let (_, &next) = by_fitness
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, i)| !selected.contains(i))
+                    .max_by_key(|&(rank, &i)| (min_dist[i], std::cmp::Reverse(rank)))
+                    .unwrap();
+                selected.push(next);
+            }
+            by_fitness = selected;
+        }
+    }
+    by_fitness
+}
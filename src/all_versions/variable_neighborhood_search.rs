@@ -0,0 +1,117 @@
+/// Variable neighbourhood descent and search over `neighborhoods::Neighborhood`s
+///
+/// - `vnd`: try the neighbourhoods in order; after any improving move go back
+///   to the first one, otherwise move on to the next. Stops when none improves.
+/// - `vns`: basic VNS around `vnd`. Shaking at level `k` applies `k` random
+///   moves, cycling through the neighbourhoods; an improved tour resets `k` to 1,
+///   otherwise `k` grows up to `max_shake` and wraps around.
+use std::time::Instant;
+use crate::all_versions::cost_tracker::CostTracker;
+use crate::all_versions::neighborhoods::*;
+use crate::all_versions::utils::*;
+
+#[derive(Clone, Debug)]
+pub struct VnsConfig {
+    pub improvement: Improvement,
+    /// Largest shaking level (random moves per shake)
+    pub max_shake: usize,
+    /// One iteration = one shake + descent
+    pub stop: StopCondition,
+    pub seed: u64,
+}
+
+impl Default for VnsConfig {
+    fn default() -> Self {
+        Self {
+            improvement: Improvement::First,
+            max_shake: 5,
+            stop: StopCondition { max_iterations: 200, time_limit: None, patience: 100 },
+            seed: 121,
+        }
+    }
+}
+
+/// Descend through `neighborhoods` in order until none improves `tour`.
+/// Returns the total length saved.
+pub fn vnd(tour: &mut [usize], cities: &[City], neighborhoods: &[Box<dyn Neighborhood>], improvement: Improvement) -> f64 {
+    descend(tour, cities, neighborhoods, improvement, &mut vec![0; neighborhoods.len()])
+}
+
+/// `vnd`, counting the improving moves made by each neighbourhood in `moves`
+fn descend(tour: &mut [usize], cities: &[City], neighborhoods: &[Box<dyn Neighborhood>], improvement: Improvement, moves: &mut [usize]) -> f64 {
+    if tour.len() < 5 {
+        return 0.0;
+    }
+    let mut saved = 0.0;
+    let mut l = 0;
+    while l < neighborhoods.len() {
+        match neighborhoods[l].improve(tour, cities, improvement) {
+            Some(gain) => {
+                saved += gain;
+                moves[l] += 1;
+                l = 0;
+            }
+            None => l += 1,
+        }
+    }
+    saved
+}
+
+/// Run `vnd` from `tour` and return the result
+#[allow(dead_code)]
+pub fn variable_neighborhood_descent(tour: &[usize], cities: &[City], neighborhoods: &[Box<dyn Neighborhood>], improvement: Improvement) -> (Vec<usize>, f64) {
+    let mut best = tour.to_vec();
+    let mut moves = vec![0; neighborhoods.len()];
+    descend(&mut best, cities, neighborhoods, improvement, &mut moves);
+    let summary: Vec<String> = neighborhoods
+        .iter()
+        .zip(&moves)
+        .map(|(nb, m)| format!("{} = {}", nb.name(), m))
+        .collect();
+    println!("Improving moves: {}", summary.join(", "));
+    let cost = compute_total_distance(&best, cities);
+    (best, cost)
+}
+
+/// Basic VNS from `tour`
+#[allow(dead_code)]
+pub fn variable_neighborhood_search(tour: &[usize], cities: &[City], neighborhoods: &[Box<dyn Neighborhood>], config: &VnsConfig) -> (Vec<usize>, f64) {
+    let (mut best, best_length) = variable_neighborhood_descent(tour, cities, neighborhoods, config.improvement);
+    if best.len() < 5 || neighborhoods.is_empty() {
+        return (best, best_length);
+    }
+    let mut best_cost = CostTracker::new(&best, cities);
+    let start = Instant::now();
+    let mut rng = stream_rng(config.seed, 0, 0);
+    let max_shake = config.max_shake.max(1);
+
+    let mut k = 1;
+    let mut iteration = 0;
+    let mut since_improvement = 0;
+    while !config.stop.should_stop(iteration, since_improvement, start) {
+        let mut candidate = best.clone();
+        let mut cost = best_cost.clone();
+        for s in 0..k {
+            cost.saved(neighborhoods[(iteration + s) % neighborhoods.len()].shake(&mut candidate, cities, &mut rng));
+        }
+        cost.saved(vnd(&mut candidate, cities, neighborhoods, config.improvement));
+
+        if cost.cost() < best_cost.cost() - 1e-9 {
+            cost.checkpoint(&candidate, cities);
+            best = candidate;
+            best_cost = cost;
+            k = 1;
+            since_improvement = 0;
+        } else {
+            k = k % max_shake + 1;
+            since_improvement += 1;
+        }
+
+        if iteration % 20 == 0 {
+            println!("Iteration {}: best = {:.2} | shake level = {}", iteration, best_cost.cost(), k);
+        }
+        iteration += 1;
+    }
+    let cost = best_cost.resync(&best, cities);
+    (best, cost)
+}
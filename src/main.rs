@@ -36,6 +36,27 @@ use all_versions::par_topkplus::*;
 use all_versions::ga_baseline::*;
 use all_versions::ga_config::*;
 use all_versions::par_ga::*;
+use all_versions::simulated_annealing::*;
+use all_versions::iterated_local_search::*;
+use all_versions::ant_colony::*;
+use all_versions::tabu_search::*;
+use all_versions::large_neighborhood_search::*;
+use all_versions::guided_local_search::*;
+use all_versions::neighborhoods::*;
+use all_versions::variable_neighborhood_search::*;
+use all_versions::geometric_partition::*;
+use all_versions::tour_segment::*;
+use all_versions::multilevel::*;
+use all_versions::portfolio::*;
+use all_versions::algorithm_selection::*;
+use all_versions::exact::*;
+use all_versions::lower_bound::*;
+use all_versions::construction::{nearest_neighbor_tour, Construction};
+use all_versions::candidate_set::*;
+use all_versions::ga_checkpoint::*;
+use all_versions::ga_adaptive::*;
+
+use std::time::Instant;
 
 fn main() {
     // ========== Correctness Test (n = 10) ==========
@@ -43,16 +64,31 @@ fn main() {
     let mut tour: Vec<usize> = (0..10).collect();
     shuffle_tour(&mut tour);
     println!("✅ Initial tour: {:?}, cost = {:.2}", tour, compute_total_distance(&tour, &cities));
+    let optimum = solve_exact(&cities, 1_000_000);
+    println!("✅ Optimal cost (Held–Karp): {:.2}", optimum.cost);
 
     let (_, cost) = two_opt_seq(&tour, &cities);
     println!("▶️  two_opt_seq: {:.2}", cost);
 
+    for construction in Construction::ALL {
+        let start = construction.build(&cities, 121);
+        println!(
+            "▶️  construction `{}`: {:.2} -> two_opt_seq {:.2}",
+            construction.name(),
+            compute_total_distance(&start, &cities),
+            two_opt_seq(&start, &cities).1
+        );
+    }
+
     let (_, cost) = par_prototype(&tour, &cities);
     println!("▶️  par_prototype: {:.2}", cost);
 
     let (_, cost) = par_topk(&tour, &cities, 2);
     println!("▶️  par_topk (k=2): {:.2}", cost);
 
+    let (_, cost) = segment_parallel_2opt(&tour, &cities, &SegmentConfig::default());
+    println!("▶️  segment_parallel_2opt: {:.2}", cost);
+
     let (_, cost) = par_topkplus(&tour, &cities, 2, 1e-6);
     println!("▶️  par_topkplus (k=2, δ=1e-6): {:.2}", cost);
 
@@ -68,6 +104,77 @@ fn main() {
     let (_, cost) = multi_2opt_random_insert(&tour, &cities);
     println!("▶️  random_insert_ver3_multi2opt: {:.2}", cost);
 
+    let (_, cost) = simulated_annealing(&tour, &cities, &SaConfig::default());
+    println!("▶️  simulated_annealing: {:.2}", cost);
+
+    let (_, cost) = parallel_tempering(&tour, &cities, &SaConfig::default());
+    println!("▶️  parallel_tempering: {:.2}", cost);
+
+    let (_, cost) = iterated_local_search(&tour, &cities, &IlsConfig::default());
+    println!("▶️  iterated_local_search: {:.2}", cost);
+
+    let (_, cost) = parallel_ils(&tour, &cities, &IlsConfig::default());
+    println!("▶️  parallel_ils: {:.2}", cost);
+
+    let neighborhoods = default_neighborhoods(&cities);
+    for improvement in [Improvement::First, Improvement::Best] {
+        let (_, cost) = variable_neighborhood_descent(&tour, &cities, &neighborhoods, improvement);
+        println!("▶️  variable_neighborhood_descent ({:?}): {:.2}", improvement, cost);
+    }
+
+    let (_, cost) = variable_neighborhood_search(&tour, &cities, &neighborhoods, &VnsConfig::default());
+    println!("▶️  variable_neighborhood_search: {:.2}", cost);
+
+    for partition in [Partition::KdTree, Partition::KMeans { iterations: 5 }] {
+        let (_, cost) = geometric_partition_2opt(&tour, &cities, &GeometricConfig { partition, ..GeometricConfig::default() });
+        println!("▶️  geometric_partition_2opt ({:?}): {:.2}", partition, cost);
+    }
+
+    let (_, cost) = guided_local_search(&tour, &cities, &GlsConfig::default());
+    println!("▶️  guided_local_search: {:.2}", cost);
+
+    let (_, cost) = parallel_gls(&tour, &cities, &GlsConfig::default());
+    println!("▶️  parallel_gls: {:.2}", cost);
+
+    let (_, cost) = tabu_search(&tour, &cities, &TabuConfig::default());
+    println!("▶️  tabu_search: {:.2}", cost);
+
+    let (_, cost) = large_neighborhood_search(&tour, &cities, &LnsConfig::default());
+    println!("▶️  large_neighborhood_search: {:.2}", cost);
+
+    let config = LnsConfig {
+        recreate: Recreate::Regret(3),
+        acceptance: LnsAcceptance::RecordToRecord { deviation: 0.02 },
+        ..LnsConfig::default()
+    };
+    let (_, cost) = large_neighborhood_search(&tour, &cities, &config);
+    println!("▶️  large_neighborhood_search (regret-3, record-to-record): {:.2}", cost);
+
+    for variant in [AcoVariant::AntSystem, AcoVariant::MaxMin] {
+        let config = AcoConfig { variant, ..AcoConfig::default() };
+        let (_, cost, history) = ant_colony(&cities, &config);
+        let last = history.last().unwrap();
+        println!(
+            "▶️  ant_colony ({:?}): {:.2} | {} iterations, tau [{:.2e}, {:.2e}], branching = {:.2}",
+            variant, cost, history.len(), last.tau_min, last.tau_max, last.branching
+        );
+    }
+
+    let members: Vec<PortfolioMember> = ["seq", "mult4", "ga3"]
+        .iter()
+        .filter_map(|v| PortfolioMember::preset(v, 1))
+        .collect();
+    let result = portfolio(&tour, &cities, &members, std::time::Duration::from_millis(500), 121);
+    println!("▶️  portfolio (seq + mult4 + ga3, 0.5s): {:.2} from `{}`", result.cost, result.member);
+
+    let (_, cost) = solve_auto(&tour, &cities, Some(std::time::Duration::from_millis(100))).unwrap();
+    println!("▶️  solve_auto (0.1s budget): {:.2}", cost);
+    let report = validate().unwrap();
+    println!(
+        "▶️  selector leave-one-size-out: {}/{} best, regret mean {:.2}% / max {:.2}% (always seq: {:.2}%), {} over budget",
+        report.agreements, report.cases, report.mean_regret, report.max_regret, report.seq_regret, report.over_budget
+    );
+
     let best = run_ga_baseline(&cities, 20, 100, 0.1);
     println!("▶️  ga_baseline: {:.2}", best.distance());
 
@@ -76,4 +183,134 @@ fn main() {
 
     let best = run_ga_parallel(&cities, 20, 100, 0.1, 2, 30, 20, 5);
     println!("▶️  par_ga: {:.2}", best.distance());
+
+    // ========== Checkpoint / Resume (seeded par_ga) ==========
+    let params = GaParams {
+        population_size: 20,
+        generations: 100,
+        elitism_k: 2,
+        refine_start: 30,
+        refine_every: 20,
+        top_n: 5,
+        ..GaParams::default()
+    };
+    let checkpoint = CheckpointConfig {
+        path: std::env::temp_dir().join("tsp_ga_checkpoint.txt"),
+        every: 40,
+    };
+    let full = run_ga_parallel_seeded(&cities, &params, None).unwrap();
+    // The checkpointed run leaves its last snapshot (after generation 80) on disk
+    run_ga_parallel_seeded(&cities, &params, Some(&checkpoint)).unwrap();
+    let resumed = resume_ga_parallel(&cities, &params, &checkpoint).unwrap();
+    assert_eq!(resumed.tour, full.tour, "resumed par_ga diverged from the uninterrupted run");
+    println!("▶️  par_ga resume: {:.2} (matches uninterrupted run)", resumed.distance());
+
+    let memetic_params = GaParams {
+        memetic: Some(MemeticConfig { budget: 50, ..MemeticConfig::default() }),
+        ..params.clone()
+    };
+    let best = run_ga_parallel_seeded(&cities, &memetic_params, None).unwrap();
+    println!("▶️  par_ga memetic: {:.2}", best.distance());
+
+    let seeded_params = GaParams { seed_fraction: 0.2, ..params.clone() };
+    let best = run_ga_parallel_seeded(&cities, &seeded_params, None).unwrap();
+    println!("▶️  par_ga (20% heuristic seeding): {:.2}", best.distance());
+
+    let adaptive_params = GaParams {
+        mutation_control: MutationControl::OneFifth { factor: 0.85, min_rate: 0.01, max_rate: 0.5 },
+        operator_credit: Some(CreditConfig::default()),
+        log_path: Some(std::env::temp_dir().join("tsp_ga_adaptive_log.csv")),
+        ..params.clone()
+    };
+    let best = run_ga_parallel_seeded(&cities, &adaptive_params, None).unwrap();
+    println!("▶️  par_ga (1/5th rule + operator credit): {:.2}", best.distance());
+
+    for policy in [RefinePolicy::RandomN, RefinePolicy::MostDiverseN] {
+        let policy_params = GaParams {
+            refine_policy: policy,
+            log_path: Some(std::env::temp_dir().join("tsp_ga_log.csv")),
+            ..memetic_params.clone()
+        };
+        let best = run_ga_parallel_seeded(&cities, &policy_params, None).unwrap();
+        println!("▶️  par_ga ({:?}): {:.2}", policy, best.distance());
+    }
+
+    // ========== Exact solvers agree (n <= 20) ==========
+    for (n, seed) in [(4, 1), (7, 7), (11, 121), (15, 3), (HELD_KARP_MAX, 42)] {
+        let cities = generate_cities(n, seed);
+        let (_, dp) = held_karp(&cities);
+        let start: Vec<usize> = (0..n).collect();
+        let bb = branch_and_bound(&cities, &start, 100_000_000);
+        assert!(bb.optimal, "branch-and-bound did not close the search at n = {}", n);
+        assert!(
+            (bb.cost - dp).abs() <= 1e-9 * dp,
+            "n = {}: branch-and-bound {} != Held–Karp {}",
+            n, bb.cost, dp
+        );
+        println!("✅ n = {}: Held–Karp {:.4} = branch-and-bound {:.4} ({} nodes)", n, dp, bb.cost, bb.nodes);
+    }
+
+    // ========== Optimality gaps against exact solutions ==========
+    for n in [12, 40] {
+        let cities = generate_cities(n, 121);
+        let mut tour: Vec<usize> = (0..n).collect();
+        shuffle_tour(&mut tour);
+        let exact = solve_exact(&cities, 10_000_000);
+        let lower = held_karp_bound(&cities, &LowerBoundConfig::default()).bound;
+        println!(
+            "✅ n = {}: optimum {:.2} (proven: {}, {} B&B nodes), lower bound {:.2} ({:.2}% under)",
+            n, exact.cost, exact.optimal, exact.nodes, lower, optimality_gap(exact.cost, lower)
+        );
+        let eps = 1e-9 * exact.cost;
+        assert!(exact.optimal, "n = {}: exact search did not finish", n);
+        assert!(lower <= exact.cost + eps, "n = {}: lower bound {} above the optimum {}", n, lower, exact.cost);
+        let results = [
+            ("two_opt_seq", two_opt_seq(&tour, &cities).1),
+            ("par_topkplus (k=10)", par_topkplus(&tour, &cities, 10, 1e-5).1),
+            ("optimized_ver2_multi2opt", multi_2opt_optimized2_V2(&tour, &cities).1),
+            ("random_insert_ver3_multi2opt", multi_2opt_random_insert(&tour, &cities).1),
+            ("par_ga", run_ga_parallel(&cities, 20, 100, 0.1, 2, 30, 20, 5).distance()),
+            ("simulated_annealing", simulated_annealing(&tour, &cities, &SaConfig::default()).1),
+            ("iterated_local_search", iterated_local_search(&tour, &cities, &IlsConfig::default()).1),
+            ("guided_local_search", guided_local_search(&tour, &cities, &GlsConfig::default()).1),
+        ];
+        for (name, cost) in results {
+            assert!(cost >= exact.cost - eps, "n = {}: {} found {} below the optimum {}", n, name, cost, exact.cost);
+            println!(
+                "▶️  {}: {:.2} (gap {:.2}%, {:.2}% to lower bound)",
+                name, cost, optimality_gap(cost, exact.cost), optimality_gap(cost, lower)
+            );
+        }
+        let christofides = compute_total_distance(&Construction::Christofides.build(&cities, 121), &cities);
+        assert!(
+            christofides <= 1.5 * exact.cost + eps,
+            "n = {}: Christofides {} above 3/2 of the optimum {}",
+            n, christofides, exact.cost
+        );
+        println!("▶️  christofides start tour: {:.2} ({:.3} × optimum)", christofides, christofides / exact.cost);
+    }
+
+    // ========== Candidate sets on a clustered instance (n = 1000) ==========
+    let cities = generate_clustered_cities(1000, 10, 121);
+    let tour = Construction::NearestNeighbor.build(&cities, 121);
+    let lower = held_karp_bound(&cities, &LowerBoundConfig::default()).bound;
+    for candidates in [Candidates::Nearest(8), Candidates::Quadrant(2), Candidates::Delaunay, Candidates::Alpha(5)] {
+        let set = candidates.build(&cities);
+        let (_, cost) = iterated_local_search(&tour, &cities, &IlsConfig { candidates, ..IlsConfig::default() });
+        println!(
+            "▶️  iterated_local_search, {} candidates ({:.1} per city): {:.2} ({:.2}% to lower bound)",
+            candidates.name(), set.average_size(), cost, optimality_gap(cost, lower)
+        );
+    }
+
+    // ========== Large instance (n = 100000) ==========
+    let cities = generate_cities(100_000, 121);
+    let start = Instant::now();
+    let (_, cost) = multilevel(&cities, &MultilevelConfig::default());
+    println!("▶️  multilevel (n = 100000): {:.2} in {:.2?}", cost, start.elapsed());
+    let start = Instant::now();
+    let nn = nearest_neighbor_tour(0, &cities);
+    let config = GeometricConfig { partition: Partition::KdTree, ..GeometricConfig::default() };
+    let (_, cost) = geometric_partition_2opt(&nn, &cities, &config);
+    println!("▶️  nearest neighbour + geometric_partition_2opt (n = 100000): {:.2} in {:.2?}", cost, start.elapsed());
 }
@@ -14,7 +14,16 @@
 // "mult2"     => multi_2opt_optimized1
 // "mult3"     => multi_2opt_optimized2_V2
 // "mult4"     => multi_2opt_random_insert
+// "pt"        => parallel_tempering
+// "pils"      => parallel_ils
+// "aco"       => ant_colony
+// "lns"       => large_neighborhood_search
+// "pgls"      => parallel_gls
+// "geo"       => geometric_partition_2opt
+// "segment"   => segment_parallel_2opt
 // "ga3"       => run_ga_parallel
+//
+// Every result is reported with its gap to the Held–Karp 1-tree lower bound.
 // ============================================
 
 mod all_versions;
@@ -26,7 +35,16 @@ use all_versions::optimized_ver2_multi2opt::*;
 use all_versions::random_insert_ver3_multi2opt::*;
 use all_versions::par_topkplus::*;
 use all_versions::par_ga::*;
+use all_versions::simulated_annealing::*;
+use all_versions::iterated_local_search::*;
+use all_versions::ant_colony::*;
+use all_versions::large_neighborhood_search::*;
+use all_versions::guided_local_search::*;
+use all_versions::geometric_partition::*;
+use all_versions::tour_segment::*;
 use all_versions::two_opt_seq::*;
+use all_versions::exact::optimality_gap;
+use all_versions::lower_bound::*;
 
 use std::env;
 use std::time::Instant;
@@ -37,6 +55,8 @@ fn main() {
     let cities = generate_cities(n, seed);
     let mut tour: Vec<usize> = (0..n).collect();
     shuffle_tour(&mut tour);
+    let lower = held_karp_bound(&cities, &LowerBoundConfig::default()).bound;
+    println!("📉 Held–Karp lower bound: {:.2}", lower);
 
     let versions: &[(&str, fn(&[usize], &[City]) -> f64)] = &[
         ("seq", |t, c| two_opt_seq(t, c).1),
@@ -45,6 +65,13 @@ fn main() {
         ("mult2", |t, c| multi_2opt_optimized1(t, c).1),
         ("mult3", |t, c| multi_2opt_optimized2_V2(t, c).1),
         ("mult4", |t, c| multi_2opt_random_insert(t, c).1),
+        ("pt", |t, c| parallel_tempering(t, c, &SaConfig::default()).1),
+        ("pils", |t, c| parallel_ils(t, c, &IlsConfig::default()).1),
+        ("aco", |_, c| ant_colony(c, &AcoConfig::default()).1),
+        ("lns", |t, c| large_neighborhood_search(t, c, &LnsConfig::default()).1),
+        ("pgls", |t, c| parallel_gls(t, c, &GlsConfig::default()).1),
+        ("geo", |t, c| geometric_partition_2opt(t, c, &GeometricConfig::default()).1),
+        ("segment", |t, c| segment_parallel_2opt(t, c, &SegmentConfig::default()).1),
     ];
 
     for (name, func) in versions {
@@ -54,6 +81,7 @@ fn main() {
         let cost = func(&tour_copy, &cities);
         let duration = start.elapsed();
         println!("✅ Final cost: {:.2}", cost);
+        println!("📉 Gap to lower bound: {:.2}%", optimality_gap(cost, lower));
         println!("⏱️  Time: {:.2?}", duration);
     }
 
@@ -62,5 +90,6 @@ fn main() {
     let cost = run_ga_parallel(&cities, 300, 1000, 0.10, 2, 300, 100, 10).distance();
     let duration = start.elapsed();
     println!("✅ Final cost: {:.2}", cost);
+    println!("📉 Gap to lower bound: {:.2}%", optimality_gap(cost, lower));
     println!("⏱️  Time: {:.2?}", duration);
 }
@@ -11,14 +11,42 @@
 // "mult2" => multi_2opt_optimized1, 
 // "mult3" => multi_2opt_optimized2_V2,
 // "mult4" => multi_2opt_random_insert, 
+// "sa" => simulated_annealing,
+// "pt" => parallel_tempering,
+// "ils" => iterated_local_search,
+// "pils" => parallel_ils,
+// "ils-quadrant" / "ils-delaunay" / "ils-alpha" => iterated_local_search on
+//   quadrant (2 per quadrant), Delaunay or alpha-nearness (5) candidate sets
+// "aco" => ant_colony (MAX-MIN Ant System),
+// "tabu" => tabu_search,
+// "lns" => large_neighborhood_search,
+// "gls" => guided_local_search,
+// "pgls" => parallel_gls,
+// "vnd" => variable_neighborhood_descent (2-opt, Or-opt, 3-opt, swap),
+// "vns" => variable_neighborhood_search,
+// "geo" => geometric_partition_2opt (k-means regions), "geokd" => with k-d regions
+// "segment" => segment_parallel_2opt (tour segments with rotating cut points)
+// "portfolio" => portfolio racing seq, mult4, ga3 and ils for 2 s
+// "auto" => solve_auto (variant selected from instance features, no budget)
+// "auto1s" => solve_auto with a 1 s budget
 // "ga1" => run_ga_baseline,
 // "ga2" => run_ga_config, 
 // "ga3" => run_ga_parallel
+// "memetic" => run_ga_parallel_seeded with per-child local search
+// "ga3seeded" => run_ga_parallel_seeded with 20% heuristic initial population
+// "ga3adaptive" => run_ga_parallel_seeded with diversity-driven rate + operator credit
+// "start" => no search, just the start tour
+//
+// An optional second argument picks the start tour (default: random shuffle):
+// random, nn, greedy, nearest, farthest, cheapest, insertion, hull, hilbert,
+// christofides, mst-matching, mst.
 //
 // Usage (example):
 //   cargo run --bin main_scalability topk
+//   cargo run --bin main_scalability seq mst-matching
 //
 // Tests with sizes: [50, 100, 200, 500, 1000]
+// Each result is reported with its gap to the Held–Karp 1-tree lower bound.
 // ============================================
 
 mod all_versions;
@@ -34,6 +62,23 @@ use all_versions::par_topkplus::*;
 use all_versions::ga_baseline::*;
 use all_versions::ga_config::*;
 use all_versions::par_ga::*;
+use all_versions::simulated_annealing::*;
+use all_versions::iterated_local_search::*;
+use all_versions::ant_colony::*;
+use all_versions::tabu_search::*;
+use all_versions::large_neighborhood_search::*;
+use all_versions::guided_local_search::*;
+use all_versions::neighborhoods::*;
+use all_versions::variable_neighborhood_search::*;
+use all_versions::geometric_partition::*;
+use all_versions::tour_segment::*;
+use all_versions::portfolio::*;
+use all_versions::algorithm_selection::*;
+use all_versions::exact::optimality_gap;
+use all_versions::lower_bound::*;
+use all_versions::construction::Construction;
+use all_versions::candidate_set::Candidates;
+use all_versions::ga_adaptive::*;
 use all_versions::two_opt_seq::*;
 
 use std::env;
@@ -42,6 +87,16 @@ use std::time::Instant;
 fn main() {
     let args: Vec<String> = env::args().collect();
     let version = args.get(1).map(String::as_str).unwrap_or("seq");
+    let construction = match args.get(2) {
+        Some(name) => match Construction::from_name(name) {
+            Some(c) => Some(c),
+            None => {
+                eprintln!("❌ Unknown start tour: `{}`", name);
+                return;
+            }
+        },
+        None => None,
+    };
 
     let sizes = [50, 100, 200, 500, 1000];
     let seed = 121;
@@ -53,6 +108,11 @@ fn main() {
         let cities = generate_cities(n, seed);
         let mut tour: Vec<usize> = (0..n).collect();
         shuffle_tour(&mut tour);
+        if let Some(construction) = construction {
+            tour = construction.build(&cities, seed);
+            println!("Start tour `{}`: {:.2}", construction.name(), compute_total_distance(&tour, &cities));
+        }
+        let lower = held_karp_bound(&cities, &LowerBoundConfig::default()).bound;
 
         let start = Instant::now();
         let cost = match version {
@@ -64,9 +124,58 @@ fn main() {
             "mult2" => multi_2opt_optimized1(&tour, &cities).1,
             "mult3" => multi_2opt_optimized2_V2(&tour, &cities).1,
             "mult4" => multi_2opt_random_insert(&tour, &cities).1,
+            "sa" => simulated_annealing(&tour, &cities, &SaConfig::default()).1,
+            "pt" => parallel_tempering(&tour, &cities, &SaConfig::default()).1,
+            "ils" => iterated_local_search(&tour, &cities, &IlsConfig::default()).1,
+            "pils" => parallel_ils(&tour, &cities, &IlsConfig::default()).1,
+            "ils-quadrant" => iterated_local_search(&tour, &cities, &IlsConfig { candidates: Candidates::Quadrant(2), ..IlsConfig::default() }).1,
+            "ils-delaunay" => iterated_local_search(&tour, &cities, &IlsConfig { candidates: Candidates::Delaunay, ..IlsConfig::default() }).1,
+            "ils-alpha" => iterated_local_search(&tour, &cities, &IlsConfig { candidates: Candidates::Alpha(5), ..IlsConfig::default() }).1,
+            "aco" => ant_colony(&cities, &AcoConfig::default()).1,
+            "tabu" => tabu_search(&tour, &cities, &TabuConfig::default()).1,
+            "lns" => large_neighborhood_search(&tour, &cities, &LnsConfig::default()).1,
+            "gls" => guided_local_search(&tour, &cities, &GlsConfig::default()).1,
+            "pgls" => parallel_gls(&tour, &cities, &GlsConfig::default()).1,
+            "vnd" => variable_neighborhood_descent(&tour, &cities, &default_neighborhoods(&cities), Improvement::First).1,
+            "vns" => variable_neighborhood_search(&tour, &cities, &default_neighborhoods(&cities), &VnsConfig::default()).1,
+            "geo" => geometric_partition_2opt(&tour, &cities, &GeometricConfig::default()).1,
+            "geokd" => geometric_partition_2opt(&tour, &cities, &GeometricConfig { partition: Partition::KdTree, ..GeometricConfig::default() }).1,
+            "segment" => segment_parallel_2opt(&tour, &cities, &SegmentConfig::default()).1,
+            "portfolio" => {
+                let threads = rayon::current_num_threads();
+                let members: Vec<PortfolioMember> = [("seq", 1), ("ils", 1), ("mult4", threads / 2), ("ga3", threads / 2)]
+                    .iter()
+                    .filter_map(|&(v, t)| PortfolioMember::preset(v, t))
+                    .collect();
+                let result = portfolio(&tour, &cities, &members, std::time::Duration::from_secs(2), seed);
+                println!("Best from `{}`; rounds: {:?}", result.member, result.rounds);
+                result.cost
+            }
+            "auto" => solve_auto(&tour, &cities, None).unwrap().1,
+            "auto1s" => solve_auto(&tour, &cities, Some(std::time::Duration::from_secs(1))).unwrap().1,
+            "start" => compute_total_distance(&tour, &cities),
             "ga1" => run_ga_baseline(&cities, 100, 300, 0.1).distance(),
             "ga2" => run_ga_config(&cities, 100, 300, 0.1, 5, 100, 100, 10).distance(),
             "ga3" => run_ga_parallel(&cities, 100, 300, 0.1, 5, 100, 100, 10).distance(),
+            "ga3seeded" => {
+                let params = GaParams { seed_fraction: 0.2, ..GaParams::default() };
+                run_ga_parallel_seeded(&cities, &params, None).unwrap().distance()
+            }
+            "ga3adaptive" => {
+                let params = GaParams {
+                    mutation_control: MutationControl::Diversity { min_rate: 0.05, max_rate: 0.6 },
+                    operator_credit: Some(CreditConfig::default()),
+                    ..GaParams::default()
+                };
+                run_ga_parallel_seeded(&cities, &params, None).unwrap().distance()
+            }
+            "memetic" => {
+                let params = GaParams {
+                    memetic: Some(MemeticConfig { budget: 4 * n, ..MemeticConfig::default() }),
+                    ..GaParams::default()
+                };
+                run_ga_parallel_seeded(&cities, &params, None).unwrap().distance()
+            }
             _ => {
                 eprintln!("❌ Unknown version: `{}`", version);
                 return;
@@ -75,6 +184,7 @@ fn main() {
         let duration = start.elapsed();
 
         println!("✅ Final cost: {:.2}", cost);
+        println!("📉 Gap to lower bound ({:.2}): {:.2}%", lower, optimality_gap(cost, lower));
         println!("⏱️ Time: {:.2?}", duration);
     }
 }
\ No newline at end of file